@@ -0,0 +1,163 @@
+use crate::{Isin, IsinError};
+use std::collections::HashMap;
+
+/// Per-bin weighted mean of observations
+#[derive(Debug, Clone)]
+pub struct BinAccumulator {
+    sums: Vec<f64>,
+    weights: Vec<f64>,
+}
+
+impl BinAccumulator {
+    /// Create an empty accumulator covering every bin of a grid
+    /// # Arguments
+    /// * `isin` - The grid the bins belong to
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// let acc = l3bin::BinAccumulator::new(&isin);
+    /// ```
+    pub fn new(isin: &Isin) -> BinAccumulator {
+        BinAccumulator {
+            sums: vec![0.0; isin.total_bins()],
+            weights: vec![0.0; isin.total_bins()],
+        }
+    }
+
+    /// Add an observation with a weight of one
+    /// # Arguments
+    /// * `bin` - The bin of the observation
+    /// * `value` - The observed value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// let mut acc = l3bin::BinAccumulator::new(&isin);
+    /// acc.add(100, 2.0).unwrap();
+    /// ```
+    pub fn add(&mut self, bin: usize, value: f64) -> Result<(), IsinError> {
+        self.add_weighted(bin, value, 1.0)
+    }
+
+    /// Add an observation whose weight halves every `half_life_days`
+    /// # Arguments
+    /// * `bin` - The bin of the observation
+    /// * `value` - The observed value
+    /// * `age_days` - The age of the observation in days
+    /// * `half_life_days` - The half-life of the weight in days, must be positive
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// let mut acc = l3bin::BinAccumulator::new(&isin);
+    /// acc.add_decayed(100, 2.0, 3.0, 8.0).unwrap();
+    /// ```
+    /// # Note
+    /// The weight is `0.5^(age_days / half_life_days)`.
+    pub fn add_decayed(
+        &mut self,
+        bin: usize,
+        value: f64,
+        age_days: f64,
+        half_life_days: f64,
+    ) -> Result<(), IsinError> {
+        if half_life_days <= 0.0 || half_life_days.is_nan() {
+            return Err(IsinError::InvalidParameter(format!(
+                "half-life must be positive, got {}",
+                half_life_days
+            )));
+        }
+
+        self.add_weighted(bin, value, 0.5_f64.powf(age_days / half_life_days))
+    }
+
+    /// Get the weighted mean of every bin, `None` for bins without observations
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// let mut acc = l3bin::BinAccumulator::new(&isin);
+    /// acc.add(100, 2.0).unwrap();
+    /// let means = acc.finish();
+    /// println!("Mean: {:?}", means[99]);
+    /// ```
+    /// # Note
+    /// The returned vector is indexed by `bin - 1`.
+    pub fn finish(self) -> Vec<Option<f64>> {
+        self.sums
+            .iter()
+            .zip(self.weights.iter())
+            .map(|(&sum, &weight)| {
+                if weight > 0.0 {
+                    Some(sum / weight)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn add_weighted(&mut self, bin: usize, value: f64, weight: f64) -> Result<(), IsinError> {
+        if bin < 1 || bin > self.sums.len() {
+            return Err(IsinError::InvalidBinRange {
+                bin,
+                min: 1,
+                max: self.sums.len(),
+            });
+        }
+
+        self.sums[bin - 1] += weight * value;
+        self.weights[bin - 1] += weight;
+
+        Ok(())
+    }
+}
+
+/// Per-bin count and sum of observations, holding only the bins with data
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BinStats {
+    stats: HashMap<usize, (u64, f64)>,
+}
+
+impl BinStats {
+    pub(crate) fn add(&mut self, bin: usize, value: f64) {
+        let entry = self.stats.entry(bin).or_insert((0, 0.0));
+        entry.0 += 1;
+        entry.1 += value;
+    }
+
+    /// Get the number of observations of a bin, 0 for bins without data
+    /// # Arguments
+    /// * `bin` - A bin value
+    pub fn count(&self, bin: usize) -> u64 {
+        self.stats.get(&bin).map_or(0, |s| s.0)
+    }
+
+    /// Get the sum of the observations of a bin, `None` for bins without data
+    /// # Arguments
+    /// * `bin` - A bin value
+    pub fn sum(&self, bin: usize) -> Option<f64> {
+        self.stats.get(&bin).map(|s| s.1)
+    }
+
+    /// Get the mean of the observations of a bin, `None` for bins without data
+    /// # Arguments
+    /// * `bin` - A bin value
+    pub fn mean(&self, bin: usize) -> Option<f64> {
+        self.stats.get(&bin).map(|s| s.1 / s.0 as f64)
+    }
+
+    /// Get the bins with data in increasing order
+    pub fn bins(&self) -> Vec<usize> {
+        let mut bins: Vec<usize> = self.stats.keys().copied().collect();
+        bins.sort_unstable();
+        bins
+    }
+
+    /// Get the number of bins with data
+    pub fn len(&self) -> usize {
+        self.stats.len()
+    }
+
+    /// Check whether no bin has data
+    pub fn is_empty(&self) -> bool {
+        self.stats.is_empty()
+    }
+}
@@ -4,7 +4,7 @@ use l3bin::Isin;
 fn main() {
     let isin = Isin::new(18);
 
-    let res = isin.bin2bounds(&[367]);
+    let res = isin.bin2bounds(&[367]).unwrap();
 
     println!("{:?}", res);
 }
@@ -0,0 +1,77 @@
+use crate::IsinError;
+
+/// Size in bytes of a packed BinList record
+pub const BINLIST_RECORD_SIZE: usize = 13;
+
+/// A record of the BinList of a NASA L3b file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BinListEntry {
+    /// The 1-based bin number
+    pub bin_num: u32,
+    /// The number of observations in the bin
+    pub nobs: u16,
+    /// The number of scenes contributing to the bin
+    pub nscenes: u16,
+    /// The sum of the observation weights
+    pub weights: f32,
+    /// The quality flags of the bin
+    pub flags: u8,
+}
+
+/// Parse a BinList dataset extracted from an L3b file
+/// # Arguments
+/// * `bytes` - The packed records
+/// # Example
+/// ```
+/// let bytes = [1, 0, 0, 0, 3, 0, 1, 0, 0, 0, 64, 64, 0];
+/// let entries = l3bin::binlist::parse_binlist(&bytes).unwrap();
+/// assert_eq!(entries[0].bin_num, 1);
+/// assert_eq!(entries[0].weights, 3.0);
+/// ```
+/// # Note
+/// Each record is 13 packed little-endian bytes: `bin_num: u32`, `nobs: u16`, `nscenes: u16`,
+/// `weights: f32` and `flags: u8`. Records are returned in the order of the buffer, which is
+/// increasing `bin_num` in L3b files.
+pub fn parse_binlist(bytes: &[u8]) -> Result<Vec<BinListEntry>, IsinError> {
+    if !bytes.len().is_multiple_of(BINLIST_RECORD_SIZE) {
+        return Err(IsinError::InvalidParameter(format!(
+            "a BinList is made of {}-byte records, got {} bytes",
+            BINLIST_RECORD_SIZE,
+            bytes.len()
+        )));
+    }
+
+    Ok(bytes
+        .chunks_exact(BINLIST_RECORD_SIZE)
+        .map(|r| BinListEntry {
+            bin_num: u32::from_le_bytes([r[0], r[1], r[2], r[3]]),
+            nobs: u16::from_le_bytes([r[4], r[5]]),
+            nscenes: u16::from_le_bytes([r[6], r[7]]),
+            weights: f32::from_le_bytes([r[8], r[9], r[10], r[11]]),
+            flags: r[12],
+        })
+        .collect())
+}
+
+/// Write BinList records in the layout read by `parse_binlist`
+/// # Arguments
+/// * `entries` - The records to write
+/// # Example
+/// ```
+/// use l3bin::binlist::{parse_binlist, write_binlist};
+/// let bytes = [1, 0, 0, 0, 3, 0, 1, 0, 0, 0, 64, 64, 0];
+/// assert_eq!(write_binlist(&parse_binlist(&bytes).unwrap()), bytes);
+/// ```
+pub fn write_binlist(entries: &[BinListEntry]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(entries.len() * BINLIST_RECORD_SIZE);
+
+    for entry in entries {
+        bytes.extend_from_slice(&entry.bin_num.to_le_bytes());
+        bytes.extend_from_slice(&entry.nobs.to_le_bytes());
+        bytes.extend_from_slice(&entry.nscenes.to_le_bytes());
+        bytes.extend_from_slice(&entry.weights.to_le_bytes());
+        bytes.push(entry.flags);
+    }
+
+    bytes
+}
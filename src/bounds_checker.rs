@@ -0,0 +1,95 @@
+use crate::IsinError;
+
+pub(crate) const MIN_LON: f64 = -180.0;
+pub(crate) const MAX_LON: f64 = 180.0;
+pub(crate) const MIN_LAT: f64 = -90.0;
+pub(crate) const MAX_LAT: f64 = 90.0;
+
+pub(crate) fn check_lon(lon: f64) -> Result<(), IsinError> {
+    if !(MIN_LON..=MAX_LON).contains(&lon) {
+        return Err(IsinError::InvalidLongitude {
+            lon,
+            min: MIN_LON,
+            max: MAX_LON,
+        });
+    }
+
+    Ok(())
+}
+
+pub(crate) fn check_lat(lat: f64) -> Result<(), IsinError> {
+    if !(MIN_LAT..=MAX_LAT).contains(&lat) {
+        return Err(IsinError::InvalidLatitude {
+            lat,
+            min: MIN_LAT,
+            max: MAX_LAT,
+        });
+    }
+
+    Ok(())
+}
+
+pub(crate) fn clamp_lon(lon: f64) -> f64 {
+    lon.clamp(MIN_LON, MAX_LON)
+}
+
+pub(crate) fn clamp_lat(lat: f64) -> f64 {
+    lat.clamp(MIN_LAT, MAX_LAT)
+}
+
+/// Convert a longitude between the [-180, 180] and [0, 360] conventions
+/// # Arguments
+/// * `lon` - A longitude value
+/// * `to_360` - Convert to [0, 360] if true, to [-180, 180] otherwise
+/// # Example
+/// ```
+/// use l3bin::bounds_checker::normalize_longitude;
+/// assert_eq!(normalize_longitude(-90.0, true), 270.0);
+/// assert_eq!(normalize_longitude(270.0, false), -90.0);
+/// ```
+/// # Note
+/// Only values on the other side of the meridian are shifted by 360: negative values when
+/// converting to [0, 360], and values above 180 when converting to [-180, 180]. So 180 stays 180
+/// both ways, -180 becomes 180 and 360 becomes 0.
+pub fn normalize_longitude(lon: f64, to_360: bool) -> f64 {
+    if to_360 && lon < 0.0 {
+        lon + 360.0
+    } else if !to_360 && lon > 180.0 {
+        lon - 360.0
+    } else {
+        lon
+    }
+}
+
+/// Clamp a latitude into [-90, 90]
+/// # Arguments
+/// * `lat` - A latitude value
+/// # Example
+/// ```
+/// use l3bin::bounds_checker::constrain_lat;
+/// assert_eq!(constrain_lat(91.0), 90.0);
+/// ```
+/// # Note
+/// Latitudes past a pole are clamped to it, unlike `constrain_lon` which wraps.
+pub fn constrain_lat(lat: f64) -> f64 {
+    clamp_lat(lat)
+}
+
+/// Wrap a longitude into [-180, 180]
+/// # Arguments
+/// * `lon` - A longitude value
+/// # Example
+/// ```
+/// use l3bin::bounds_checker::constrain_lon;
+/// assert_eq!(constrain_lon(181.0), -179.0);
+/// ```
+/// # Note
+/// Longitudes are periodic, so values out of range wrap around the antimeridian instead of being
+/// clamped like `constrain_lat`. Values already in range, including -180 and 180, are unchanged.
+pub fn constrain_lon(lon: f64) -> f64 {
+    if (MIN_LON..=MAX_LON).contains(&lon) {
+        return lon;
+    }
+
+    (lon + 180.0).rem_euclid(360.0) - 180.0
+}
@@ -0,0 +1,126 @@
+use std::io::{BufRead, BufReader, Read, Write};
+
+use crate::bounds_checker::{check_lat, check_lon};
+use crate::{BinAccumulator, Isin, IsinError};
+
+/// Bin and average the observations of a `lon,lat,value` CSV in a single pass
+/// # Arguments
+/// * `isin` - The grid to bin into
+/// * `reader` - The CSV input, read line by line
+/// # Example
+/// ```
+/// let isin = l3bin::Isin::new(18);
+/// let data = "lon,lat,value\n10.0,45.0,1.0\n10.5,45.5,3.0\nbad,row\n";
+/// let (means, skipped) = l3bin::csv::accumulate_csv(&isin, data.as_bytes()).unwrap();
+/// let bin = isin.lonlat2bin(&[10.0], &[45.0]).unwrap()[0];
+/// assert_eq!(means[bin - 1], Some(2.0));
+/// assert_eq!(skipped, 1);
+/// ```
+/// # Note
+/// Only one line and the per-bin sums are held in memory. The first line is taken as a header
+/// when it does not parse as a row. Empty lines are ignored, and rows that are malformed, have a
+/// non-finite value or have coordinates out of range are skipped and counted. The returned means
+/// are indexed by `bin - 1`.
+pub fn accumulate_csv<R: Read>(
+    isin: &Isin,
+    reader: R,
+) -> Result<(Vec<Option<f64>>, usize), IsinError> {
+    let mut acc = BinAccumulator::new(isin);
+    let mut skipped = 0;
+
+    for (i, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line.map_err(|e| IsinError::Io(e.to_string()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match parse_row(&line) {
+            Some((lon, lat, value)) if check_lon(lon).is_ok() && check_lat(lat).is_ok() => {
+                acc.add(isin.bin_of(lon, lat), value)?;
+            }
+            None if i == 0 => {}
+            _ => skipped += 1,
+        }
+    }
+
+    Ok((acc.finish(), skipped))
+}
+
+/// Append the bin of each `lon,lat` row of a CSV
+/// # Arguments
+/// * `isin` - The grid to bin into
+/// * `input` - The CSV input, read line by line
+/// * `output` - Where to write the CSV with the extra `bin` column
+/// # Example
+/// ```
+/// let isin = l3bin::Isin::new(18);
+/// let mut output = Vec::new();
+/// l3bin::csv::lonlat_csv_to_bins(&isin, "lon,lat\n-180,-90\n".as_bytes(), &mut output).unwrap();
+/// assert_eq!(String::from_utf8(output).unwrap(), "lon,lat,bin\n-180,-90,1\n");
+/// ```
+/// # Note
+/// The first line is taken as a header when it does not parse as a row, and gets a `bin` column.
+/// Empty lines are dropped. Unlike `accumulate_csv`, malformed rows and coordinates out of range
+/// are not skipped: they stop the conversion with an error naming the line, so that the output
+/// always has one row per input row.
+pub fn lonlat_csv_to_bins<R: Read, W: Write>(
+    isin: &Isin,
+    input: R,
+    mut output: W,
+) -> Result<(), IsinError> {
+    let io_error = |e: std::io::Error| IsinError::Io(e.to_string());
+
+    for (i, line) in BufReader::new(input).lines().enumerate() {
+        let line = line.map_err(io_error)?;
+        let line = line.trim_end();
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match parse_lonlat(line) {
+            Some((lon, lat)) => {
+                check_lon(lon)?;
+                check_lat(lat)?;
+                let bin = isin.bin_of(lon, lat) - isin.bin_shift();
+                writeln!(output, "{},{}", line, bin).map_err(io_error)?;
+            }
+            None if i == 0 => writeln!(output, "{},bin", line).map_err(io_error)?,
+            None => {
+                return Err(IsinError::InvalidParameter(format!(
+                    "line {} is not a lon,lat row: {:?}",
+                    i + 1,
+                    line
+                )))
+            }
+        }
+    }
+
+    output.flush().map_err(io_error)
+}
+
+fn parse_lonlat(line: &str) -> Option<(f64, f64)> {
+    let mut fields = line.split(',').map(|field| field.trim().parse::<f64>());
+
+    let lon = fields.next()?.ok()?;
+    let lat = fields.next()?.ok()?;
+
+    if fields.next().is_some() {
+        return None;
+    }
+
+    Some((lon, lat))
+}
+
+fn parse_row(line: &str) -> Option<(f64, f64, f64)> {
+    let mut fields = line.split(',').map(|field| field.trim().parse::<f64>());
+
+    let lon = fields.next()?.ok()?;
+    let lat = fields.next()?.ok()?;
+    let value = fields.next()?.ok()?;
+
+    if fields.next().is_some() || !value.is_finite() {
+        return None;
+    }
+
+    Some((lon, lat, value))
+}
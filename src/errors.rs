@@ -0,0 +1,127 @@
+use std::fmt;
+
+/// Errors returned by the fallible `Isin` methods
+///
+/// New variants may be added in minor releases, so matches outside of this crate need a wildcard
+/// arm.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum IsinError {
+    /// A bin number is outside of the valid range of the grid
+    InvalidBinRange { bin: usize, min: usize, max: usize },
+    /// A latitude is outside of the valid range
+    InvalidLatitude { lat: f64, min: f64, max: f64 },
+    /// A longitude is outside of the valid range
+    InvalidLongitude { lon: f64, min: f64, max: f64 },
+    /// Two inputs that must have the same length do not
+    LengthMismatch { expected: usize, found: usize },
+    /// Latitudes expected in non-decreasing order decrease at this index
+    UnsortedLatitude { index: usize },
+    /// The coordinates look like radians rather than degrees
+    SuspectedRadians,
+    /// The grid arrays are inconsistent with each other
+    InvalidGrid(String),
+    /// The number of bins of a grid with this many rows does not fit in a `usize`
+    GridTooLarge { numrows: usize },
+    /// A parameter has a value that cannot be used
+    InvalidParameter(String),
+    /// Reading the input failed
+    Io(String),
+}
+
+impl fmt::Display for IsinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IsinError::InvalidBinRange { bin, min, max } => {
+                write!(
+                    f,
+                    "bin {} is outside of the valid range {}..={}",
+                    bin, min, max
+                )
+            }
+            IsinError::InvalidLatitude { lat, min, max } => {
+                write!(
+                    f,
+                    "latitude {} is outside of the valid range [{}, {}]",
+                    lat, min, max
+                )
+            }
+            IsinError::InvalidLongitude { lon, min, max } => {
+                write!(
+                    f,
+                    "longitude {} is outside of the valid range [{}, {}]",
+                    lon, min, max
+                )
+            }
+            IsinError::LengthMismatch { expected, found } => {
+                write!(f, "expected {} values, found {}", expected, found)
+            }
+            IsinError::UnsortedLatitude { index } => {
+                write!(
+                    f,
+                    "latitude at index {} is smaller than the previous one",
+                    index
+                )
+            }
+            IsinError::SuspectedRadians => {
+                write!(f, "coordinates look like radians, expected degrees")
+            }
+            IsinError::InvalidGrid(msg) => write!(f, "invalid grid: {}", msg),
+            IsinError::GridTooLarge { numrows } => {
+                write!(f, "a grid of {} rows has too many bins", numrows)
+            }
+            IsinError::InvalidParameter(msg) => write!(f, "invalid parameter: {}", msg),
+            IsinError::Io(msg) => write!(f, "i/o error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for IsinError {}
+
+/// Error returned when parsing an unknown satellite name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSatelliteError {
+    name: String,
+}
+
+impl ParseSatelliteError {
+    pub(crate) fn new(name: &str) -> ParseSatelliteError {
+        ParseSatelliteError {
+            name: name.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParseSatelliteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown satellite {:?}", self.name)
+    }
+}
+
+impl std::error::Error for ParseSatelliteError {}
+
+/// Errors returned when creating a `Satellite`
+///
+/// New variants may be added in minor releases, so matches outside of this crate need a wildcard
+/// arm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SatelliteError {
+    /// The grid would have no rows
+    ZeroRows,
+    /// The resolution is not a positive finite number
+    InvalidResolution,
+}
+
+impl fmt::Display for SatelliteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SatelliteError::ZeroRows => write!(f, "the number of latitude rows must be positive"),
+            SatelliteError::InvalidResolution => {
+                write!(f, "the resolution must be a positive finite number")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SatelliteError {}
@@ -0,0 +1,118 @@
+/// Mean radius of the Earth in kilometers
+pub const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Compute the great-circle distance between two points with the haversine formula
+/// # Arguments
+/// * `a` - The (lon, lat) of the first point in degrees
+/// * `b` - The (lon, lat) of the second point in degrees
+/// * `radius` - The radius of the sphere in kilometers
+/// # Example
+/// ```
+/// use l3bin::geo_math::{haversine_km, EARTH_RADIUS_KM};
+/// let km = haversine_km((-0.1278, 51.5074), (2.3522, 48.8566), EARTH_RADIUS_KM);
+/// println!("London - Paris: {:?} km", km);
+/// ```
+pub fn haversine_km(a: (f64, f64), b: (f64, f64), radius: f64) -> f64 {
+    let (lon1, lat1) = (a.0.to_radians(), a.1.to_radians());
+    let (lon2, lat2) = (b.0.to_radians(), b.1.to_radians());
+
+    let h = f64::sin((lat2 - lat1) / 2.0).powi(2)
+        + lat1.cos() * lat2.cos() * f64::sin((lon2 - lon1) / 2.0).powi(2);
+
+    2.0 * radius * f64::asin(h.sqrt().min(1.0))
+}
+
+/// Compute the initial bearing of the great circle going from one point to another
+/// # Arguments
+/// * `a` - The (lon, lat) of the start point in degrees
+/// * `b` - The (lon, lat) of the end point in degrees
+/// # Example
+/// ```
+/// let bearing = l3bin::geo_math::initial_bearing_deg((-0.1278, 51.5074), (2.3522, 48.8566));
+/// println!("Bearing: {:?}", bearing);
+/// ```
+/// # Note
+/// The bearing is in degrees clockwise from north, within [0, 360).
+pub fn initial_bearing_deg(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (lon1, lat1) = (a.0.to_radians(), a.1.to_radians());
+    let (lon2, lat2) = (b.0.to_radians(), b.1.to_radians());
+
+    let y = f64::sin(lon2 - lon1) * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * f64::cos(lon2 - lon1);
+
+    f64::atan2(y, x).to_degrees().rem_euclid(360.0)
+}
+
+/// Compute the point reached by following a great circle from a start point
+/// # Arguments
+/// * `start` - The (lon, lat) of the start point in degrees
+/// * `bearing_deg` - The initial bearing in degrees clockwise from north
+/// * `distance_km` - The distance to travel in kilometers
+/// * `radius` - The radius of the sphere in kilometers
+/// # Example
+/// ```
+/// use l3bin::geo_math::{destination_point, EARTH_RADIUS_KM};
+/// let (lon, lat) = destination_point((0.0, 0.0), 90.0, 111.2, EARTH_RADIUS_KM);
+/// println!("Lon: {:?}, Lat: {:?}", lon, lat);
+/// ```
+/// # Note
+/// The returned longitude is within [-180, 180).
+pub fn destination_point(
+    start: (f64, f64),
+    bearing_deg: f64,
+    distance_km: f64,
+    radius: f64,
+) -> (f64, f64) {
+    let (lon1, lat1) = (start.0.to_radians(), start.1.to_radians());
+    let bearing = bearing_deg.to_radians();
+    let delta = distance_km / radius;
+
+    let lat2 = f64::asin(
+        (lat1.sin() * delta.cos() + lat1.cos() * delta.sin() * bearing.cos()).clamp(-1.0, 1.0),
+    );
+    let lon2 = lon1
+        + f64::atan2(
+            bearing.sin() * delta.sin() * lat1.cos(),
+            delta.cos() - lat1.sin() * lat2.sin(),
+        );
+
+    (
+        (lon2.to_degrees() + 180.0).rem_euclid(360.0) - 180.0,
+        lat2.to_degrees(),
+    )
+}
+
+/// Compute the point at a fraction of the great circle between two points
+/// # Arguments
+/// * `a` - The (lon, lat) of the start point in degrees
+/// * `b` - The (lon, lat) of the end point in degrees
+/// * `fraction` - The fraction of the way from `a` to `b`, 0 gives `a` and 1 gives `b`
+/// # Example
+/// ```
+/// let (lon, lat) = l3bin::geo_math::intermediate_point((0.0, 0.0), (90.0, 0.0), 0.5);
+/// assert!((lon - 45.0).abs() < 1e-9 && lat.abs() < 1e-9);
+/// ```
+/// # Note
+/// The path between antipodal points is not unique; the start point is returned for identical
+/// and antipodal points. The returned longitude is within [-180, 180].
+pub fn intermediate_point(a: (f64, f64), b: (f64, f64), fraction: f64) -> (f64, f64) {
+    let (lon1, lat1) = (a.0.to_radians(), a.1.to_radians());
+    let (lon2, lat2) = (b.0.to_radians(), b.1.to_radians());
+
+    let delta = haversine_km(a, b, 1.0);
+    if delta.sin().abs() < 1e-12 {
+        return a;
+    }
+
+    let ka = f64::sin((1.0 - fraction) * delta) / delta.sin();
+    let kb = f64::sin(fraction * delta) / delta.sin();
+
+    let x = ka * lat1.cos() * lon1.cos() + kb * lat2.cos() * lon2.cos();
+    let y = ka * lat1.cos() * lon1.sin() + kb * lat2.cos() * lon2.sin();
+    let z = ka * lat1.sin() + kb * lat2.sin();
+
+    (
+        f64::atan2(y, x).to_degrees(),
+        f64::atan2(z, x.hypot(y)).to_degrees(),
+    )
+}
@@ -0,0 +1,46 @@
+use crate::{Isin, IsinError};
+
+impl Isin {
+    /// Convert bins to a GeoJSON feature collection
+    /// # Arguments
+    /// * `bins` - A vector of bin values
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let geojson = isin.bins_to_geojson(&[245535, 245536]).unwrap();
+    /// println!("{}", geojson);
+    /// ```
+    /// # Note
+    /// Each bin is a `Polygon` feature with a `bin` property. The ring goes counter-clockwise
+    /// from the south-west corner and is closed, with coordinates in degrees to 8 decimal places.
+    /// Bins never straddle the antimeridian, since the columns of every row start at -180, so
+    /// each bin is a single polygon within [-180, 180].
+    pub fn bins_to_geojson(&self, bins: &[usize]) -> Result<String, IsinError> {
+        self.check_bins(bins)?;
+
+        let features: Vec<String> = bins
+            .iter()
+            .map(|&bin| {
+                let b = self.bounds_of(bin);
+                format!(
+                    concat!(
+                        r#"{{"type":"Feature","properties":{{"bin":{bin}}},"#,
+                        r#""geometry":{{"type":"Polygon","coordinates":[["#,
+                        "[{w:.8},{s:.8}],[{e:.8},{s:.8}],[{e:.8},{n:.8}],[{w:.8},{n:.8}],",
+                        "[{w:.8},{s:.8}]]]}}}}"
+                    ),
+                    bin = bin,
+                    w = b.west,
+                    e = b.east,
+                    s = b.south,
+                    n = b.north
+                )
+            })
+            .collect();
+
+        Ok(format!(
+            r#"{{"type":"FeatureCollection","features":[{}]}}"#,
+            features.join(",")
+        ))
+    }
+}
@@ -0,0 +1,3689 @@
+use crate::bounds_checker::{
+    check_lat, check_lon, clamp_lat, clamp_lon, normalize_longitude, MAX_LON, MIN_LAT, MIN_LON,
+};
+use crate::geo_math::{haversine_km, intermediate_point, EARTH_RADIUS_KM};
+use crate::{BinStats, IsinError, Satellite};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock};
+
+#[derive(Debug, Clone)]
+pub struct Isin {
+    basebin: Vec<usize>,
+    numbin: Vec<usize>,
+    latbin: Vec<f64>,
+    colscale: Vec<f64>,
+    totbin: usize,
+    numrows: usize,
+    bin_base: BinBase,
+}
+
+impl PartialEq for Isin {
+    /// Compare the number of rows and the bin numbering, which determine every array of the grid
+    fn eq(&self, other: &Isin) -> bool {
+        self.numrows == other.numrows && self.bin_base == other.bin_base
+    }
+}
+
+impl Eq for Isin {}
+
+impl fmt::Display for Isin {
+    /// Summarize the grid, e.g. `ISIN grid: 18 rows, 412 total bins, bins/row range 3..=36`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let min = self.numbin.iter().min().unwrap_or(&0);
+        let max = self.numbin.iter().max().unwrap_or(&0);
+
+        write!(
+            f,
+            "ISIN grid: {} rows, {} total bins, bins/row range {}..={}",
+            self.numrows, self.totbin, min, max
+        )
+    }
+}
+
+/// Numbering of the first bin of the grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BinBase {
+    /// Bins are numbered from 1 to `totbin`, the NASA convention
+    #[default]
+    One,
+    /// Bins are numbered from 0 to `totbin - 1`
+    Zero,
+}
+
+/// Builder for an `Isin` grid with non-default options
+#[derive(Debug, Clone)]
+pub struct IsinBuilder {
+    numrows: usize,
+    bin_base: BinBase,
+}
+
+impl IsinBuilder {
+    /// Set the numbering of the first bin
+    /// # Arguments
+    /// * `bin_base` - The numbering used by the conversions between coordinates and bins
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::builder(4320).bin_base(l3bin::BinBase::Zero).build();
+    /// ```
+    /// # Note
+    /// The conversions are `lonlat2bin` and its `_clamped`, `_strict`, `_checked`, `_swath`,
+    /// `_sorted_by_lat` and `_360` variants, `bin2lonlat`, `bin2bounds`, their single-bin
+    /// versions, `bin_to_rowcol`/`rowcol_to_bin`, `row_for_bin` and `iter_centers`, as well as
+    /// `bin_pyramid`, `lonlat2bin_multi` and `csv::lonlat_csv_to_bins`. The other methods taking
+    /// or returning bins use the 1-based NASA numbering and fail with `InvalidParameter` on a
+    /// `BinBase::Zero` grid, so that the two numberings are never mixed. `base_bin` and
+    /// `row_bin_range` always describe the rows in the NASA numbering.
+    pub fn bin_base(mut self, bin_base: BinBase) -> IsinBuilder {
+        self.bin_base = bin_base;
+        self
+    }
+
+    /// Build the grid
+    pub fn build(self) -> Isin {
+        Isin {
+            bin_base: self.bin_base,
+            ..Isin::new(self.numrows)
+        }
+    }
+
+    /// Build a latitude index without the per-row arrays of the grid
+    /// # Example
+    /// ```
+    /// let index = l3bin::Isin::builder(4320).build_lat_index().unwrap();
+    /// assert_eq!(index.lat2row(45.0).unwrap(), 3241);
+    /// ```
+    /// # Note
+    /// For MODIS the arrays of an `Isin` take 138,240 bytes (4 arrays of 4320 values of 8 bytes),
+    /// while a `LatIndex` takes 8 bytes on 64-bit targets. The index fails like `LatIndex::try_new`
+    /// for a grid without rows.
+    pub fn build_lat_index(self) -> Result<LatIndex, IsinError> {
+        LatIndex::try_new(self.numrows)
+    }
+}
+
+/// Row lookups of an ISIN grid computed on demand
+///
+/// An `Isin` holds four arrays of one value per row, about 135 KiB for MODIS (4320 rows of 8 bytes
+/// each for `basebin`, `numbin`, `latbin` and `colscale`). A `LatIndex` only holds the number of
+/// rows and derives the row metadata from the closed-form cosine relation of the grid, for memory
+/// constrained uses that only need rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatIndex {
+    numrows: usize,
+}
+
+impl LatIndex {
+    /// Create a latitude index
+    /// # Arguments
+    /// * `numrows` - The number of rows in the ISIN grid. MODIS is 4320, SeaWiFS is 2160.
+    /// # Example
+    /// ```
+    /// let index = l3bin::LatIndex::new(4320);
+    /// ```
+    /// # Panics
+    /// Panics if `numrows` is zero, see `try_new`.
+    pub fn new(numrows: usize) -> LatIndex {
+        LatIndex::try_new(numrows).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a latitude index, failing instead of panicking on an invalid number of rows
+    /// # Arguments
+    /// * `numrows` - The number of rows in the ISIN grid, at least 1
+    /// # Example
+    /// ```
+    /// assert!(l3bin::LatIndex::try_new(4320).is_ok());
+    /// assert!(l3bin::LatIndex::try_new(0).is_err());
+    /// ```
+    pub fn try_new(numrows: usize) -> Result<LatIndex, IsinError> {
+        if numrows == 0 {
+            return Err(IsinError::InvalidParameter(
+                "an ISIN grid needs at least one row".to_string(),
+            ));
+        }
+
+        Ok(LatIndex { numrows })
+    }
+
+    /// Get the number of rows of the grid
+    pub fn num_rows(&self) -> usize {
+        self.numrows
+    }
+
+    /// Convert lat to row
+    /// # Arguments
+    /// * `lat` - A latitude value
+    /// # Example
+    /// ```
+    /// let index = l3bin::LatIndex::new(4320);
+    /// assert_eq!(index.lat2row(45.0).unwrap(), l3bin::Isin::new(4320).lat2row(45.0).unwrap());
+    /// ```
+    /// # Note
+    /// Rows are numbered from 1 like `Isin::lat2row`.
+    pub fn lat2row(&self, lat: f64) -> Result<usize, IsinError> {
+        check_lat(lat)?;
+
+        let row = ((90.0 + lat) * (self.numrows as f64) / 180.0) as usize;
+        Ok(row.min(self.numrows - 1) + 1)
+    }
+
+    /// Get the number of bins in a row
+    /// # Arguments
+    /// * `row` - A zero-based row, from south to north
+    pub fn bins_in_row(&self, row: usize) -> Option<usize> {
+        (row < self.numrows).then(|| row_width(row, self.numrows))
+    }
+
+    /// Get the latitude at the center of a row
+    /// # Arguments
+    /// * `row` - A zero-based row, from south to north
+    pub fn row_latitude(&self, row: usize) -> Option<f64> {
+        (row < self.numrows).then(|| row_center_lat(row, self.numrows))
+    }
+}
+
+/// Adjacency of the grid bins in compressed sparse row form
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdjacencyCsr {
+    /// Offsets into `indices`, the neighbors of the `i`-th bin are
+    /// `indices[indptr[i]..indptr[i + 1]]`
+    pub indptr: Vec<usize>,
+    /// Concatenated neighbor bins of every bin
+    pub indices: Vec<usize>,
+    /// The bins in increasing order for a subset of the grid, `None` when the `i`-th bin is
+    /// bin `i + 1` of the whole grid
+    pub bins: Option<Vec<usize>>,
+}
+
+impl AdjacencyCsr {
+    /// Get the neighbors of a bin
+    /// # Arguments
+    /// * `bin` - A bin value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// let csr = isin.build_adjacency_csr().unwrap();
+    /// assert!(csr.neighbors(100).is_some());
+    /// assert_eq!(csr.neighbors(0), None);
+    /// ```
+    /// # Note
+    /// `None` is returned for bins outside of the grid or of the subset the adjacency was built
+    /// for.
+    pub fn neighbors(&self, bin: usize) -> Option<&[usize]> {
+        let i = match &self.bins {
+            Some(bins) => bins.binary_search(&bin).ok()?,
+            None if bin >= 1 && bin < self.indptr.len() => bin - 1,
+            None => return None,
+        };
+
+        Some(&self.indices[self.indptr[i]..self.indptr[i + 1]])
+    }
+}
+
+/// Edges of a bin in degrees
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    /// Northern latitude
+    pub north: f64,
+    /// Southern latitude
+    pub south: f64,
+    /// Western longitude
+    pub west: f64,
+    /// Eastern longitude
+    pub east: f64,
+}
+
+impl Bounds {
+    /// Get the longitude span in degrees
+    pub fn width(&self) -> f64 {
+        self.east - self.west
+    }
+
+    /// Get the latitude span in degrees
+    pub fn height(&self) -> f64 {
+        self.north - self.south
+    }
+}
+
+/// A position in degrees
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LonLat {
+    /// Longitude
+    pub lon: f64,
+    /// Latitude
+    pub lat: f64,
+}
+
+impl LonLat {
+    /// Create a position from a longitude and a latitude
+    /// # Arguments
+    /// * `lon` - A longitude value
+    /// * `lat` - A latitude value
+    pub fn new(lon: f64, lat: f64) -> LonLat {
+        LonLat { lon, lat }
+    }
+}
+
+impl From<LonLat> for (f64, f64) {
+    fn from(p: LonLat) -> (f64, f64) {
+        (p.lon, p.lat)
+    }
+}
+
+/// A bin number, distinct from row and column indices
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bin(pub usize);
+
+impl fmt::Display for Bin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<usize> for Bin {
+    fn from(bin: usize) -> Bin {
+        Bin(bin)
+    }
+}
+
+impl From<Bin> for usize {
+    fn from(bin: Bin) -> usize {
+        bin.0
+    }
+}
+
+/// A closed ring of (lon, lat) vertices
+pub type Ring = Vec<(f64, f64)>;
+
+/// Which bins of the adjacent rows count as neighbors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// The bins containing the center longitude, plus west and east in the same row
+    Four,
+    /// Every bin whose longitude span touches the bin's span, corners included, plus west and
+    /// east in the same row
+    Eight,
+}
+
+/// Side of the equator or of the prime meridian, see `Isin::bin_hemisphere`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Hemisphere {
+    /// North of the equator
+    North,
+    /// South of the equator
+    South,
+    /// East of the prime meridian
+    East,
+    /// West of the prime meridian
+    West,
+    /// Centered on the equator
+    Equator,
+    /// Centered on the prime meridian
+    PrimeMeridian,
+}
+
+/// Comparison applied by `Isin::threshold_bins`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CmpOp {
+    /// Greater than
+    Gt,
+    /// Greater than or equal to
+    Ge,
+    /// Less than
+    Lt,
+    /// Less than or equal to
+    Le,
+}
+
+impl Isin {
+    /// Create a new ISIN grid
+    /// # Arguments
+    /// * `numrows` - The number of rows in the ISIN grid. MODIS is 4320, SeaWiFS is 2160.
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// ```
+    /// # Panics
+    /// Panics if `numrows` is zero or if the number of bins does not fit in a `usize`, see
+    /// `try_new`.
+    pub fn new(numrows: usize) -> Isin {
+        Isin::try_new(numrows).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new ISIN grid, failing instead of panicking on an invalid number of rows
+    /// # Arguments
+    /// * `numrows` - The number of rows in the ISIN grid, at least 1
+    /// # Example
+    /// ```
+    /// assert!(l3bin::Isin::try_new(4320).is_ok());
+    /// assert!(l3bin::Isin::try_new(0).is_err());
+    /// assert!(l3bin::Isin::try_new(usize::MAX / 2).is_err());
+    /// ```
+    /// # Note
+    /// A grid whose number of bins could overflow a `usize` fails with `GridTooLarge` before
+    /// anything is allocated. On 32-bit targets this limits grids to about 46,000 rows; on 64-bit
+    /// targets every grid that fits in memory is accepted.
+    pub fn try_new(numrows: usize) -> Result<Isin, IsinError> {
+        let (basebin, numbin, totbin) = row_layout(numrows)?;
+        let latbin: Vec<f64> = (0..numrows)
+            .map(|row| row_center_lat(row, numrows))
+            .collect();
+        let colscale: Vec<f64> = numbin.iter().map(|&n| n as f64 / 360.0).collect();
+
+        let isin = Isin {
+            basebin,
+            numbin,
+            latbin,
+            colscale,
+            totbin,
+            numrows,
+            bin_base: BinBase::One,
+        };
+
+        debug_assert!(isin.check_invariants().is_ok());
+
+        Ok(isin)
+    }
+
+    /// Create a builder for a grid with non-default options
+    /// # Arguments
+    /// * `numrows` - The number of rows in the ISIN grid. MODIS is 4320, SeaWiFS is 2160.
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::builder(4320).build();
+    /// ```
+    pub fn builder(numrows: usize) -> IsinBuilder {
+        IsinBuilder {
+            numrows,
+            bin_base: BinBase::One,
+        }
+    }
+
+    /// Get a grid shared by every caller asking for the same satellite
+    /// # Arguments
+    /// * `sat` - The satellite whose grid to get
+    /// # Example
+    /// ```
+    /// use l3bin::{Isin, Satellite};
+    /// let isin = Isin::shared(Satellite::Modis).unwrap();
+    /// assert_eq!(isin.num_rows(), 4320);
+    /// ```
+    /// # Note
+    /// The grid of each standard satellite is built on first use and kept for the lifetime of
+    /// the program. `Satellite::Custom` grids are not cached and are built on every call with
+    /// `try_new`, so a custom grid with an invalid number of rows is reported as an error.
+    pub fn shared(sat: Satellite) -> Result<Arc<Isin>, IsinError> {
+        static GRIDS: OnceLock<Mutex<HashMap<Satellite, Arc<Isin>>>> = OnceLock::new();
+
+        if let Satellite::Custom(numrows) = sat {
+            return Isin::try_new(numrows).map(Arc::new);
+        }
+
+        let mut grids = GRIDS
+            .get_or_init(|| Mutex::new(HashMap::new()))
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        Ok(grids
+            .entry(sat)
+            .or_insert_with(|| Arc::new(Isin::new(sat.num_latitude_rows())))
+            .clone())
+    }
+
+    /// Get the total number of bins of the grid
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// assert_eq!(isin.total_bins(), 23761676);
+    /// ```
+    pub fn total_bins(&self) -> usize {
+        self.totbin
+    }
+
+    /// Get the number of rows of the grid
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// assert_eq!(isin.num_rows(), 4320);
+    /// ```
+    pub fn num_rows(&self) -> usize {
+        self.numrows
+    }
+
+    /// Get the number of bins in a row
+    /// # Arguments
+    /// * `row` - A zero-based row, from south to north
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// assert_eq!(isin.bins_in_row(0), Some(3));
+    /// assert_eq!(isin.bins_in_row(18), None);
+    /// ```
+    pub fn bins_in_row(&self, row: usize) -> Option<usize> {
+        self.numbin.get(row).copied()
+    }
+
+    /// Check whether a row is the first or last row of the grid, around a pole
+    /// # Arguments
+    /// * `row` - A zero-based row, from south to north
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// assert!(isin.is_polar_row(0) && isin.is_polar_row(4319));
+    /// assert!(!isin.is_polar_row(1) && !isin.is_polar_row(4320));
+    /// ```
+    /// # Note
+    /// Polar rows only have a few very wide bins, 3 on the standard grids, each spanning 120
+    /// degrees of longitude. A point near a pole therefore maps to a bin whose center can be
+    /// thousands of km away in longitude, but only a few km away in distance.
+    pub fn is_polar_row(&self, row: usize) -> bool {
+        row == 0 || row + 1 == self.numrows
+    }
+
+    /// Get the first bin of a row
+    /// # Arguments
+    /// * `row` - A zero-based row, from south to north
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// assert_eq!(isin.base_bin(1), Some(4));
+    /// ```
+    /// # Note
+    /// The bin uses the 1-based NASA numbering.
+    pub fn base_bin(&self, row: usize) -> Option<usize> {
+        self.basebin.get(row).copied()
+    }
+
+    /// Get the latitude at the center of a row
+    /// # Arguments
+    /// * `row` - A zero-based row, from south to north
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// assert_eq!(isin.row_latitude(0), Some(-85.0));
+    /// ```
+    pub fn row_latitude(&self, row: usize) -> Option<f64> {
+        self.latbin.get(row).copied()
+    }
+
+    /// Convert lat to row
+    /// # Arguments
+    /// * `lat` - A latitude value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let row = isin.lat2row(45.0).unwrap();
+    /// println!("Row: {:?}", row);
+    /// ```
+    /// # Note
+    /// Rows are numbered from 1 at the south pole to `numrows` at the north pole, which belongs
+    /// to the northernmost row.
+    pub fn lat2row(&self, lat: f64) -> Result<usize, IsinError> {
+        check_lat(lat)?;
+
+        Ok(self.row_of_lat(lat) + 1)
+    }
+
+    /// Convert lonlat to bin
+    /// # Arguments
+    /// * `lon` - A vector of longitude values
+    /// * `lat` - A vector of latitude values
+    /// # Example
+    /// ```
+    /// let is = l3bin::Isin::new(4320);
+    /// let bin = is.lonlat2bin(&[45.0], &[45.0]).unwrap();
+    /// println!("Bin: {:?}", bin);
+    /// ```
+    /// # Note
+    /// Latitudes of -90 and 90 belong to the first and last rows. Those polar rows have very few
+    /// bins, see `is_polar_row`, so the center returned by `bin2lonlat` for a point near a pole
+    /// usually has a very different longitude.
+    pub fn lonlat2bin(&self, lon: &[f64], lat: &[f64]) -> Result<Vec<usize>, IsinError> {
+        check_lengths(lat.len(), lon.len())?;
+
+        let mut bin: Vec<usize> = Vec::with_capacity(lat.len());
+
+        for i in 0..lat.len() {
+            check_lon(lon[i])?;
+            check_lat(lat[i])?;
+            bin.push(self.bin_of(lon[i], lat[i]) - self.bin_shift());
+        }
+
+        Ok(bin)
+    }
+
+    /// Get the corners of a bin
+    /// # Arguments
+    /// * `bin` - A bin value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// let corners = isin.bin_corners(1).unwrap();
+    /// assert_eq!(corners[0], (-180.0, -80.0));
+    /// ```
+    /// # Note
+    /// The corners are (lon, lat) in the order north-west, north-east, south-east, south-west,
+    /// a clockwise ring when viewed on a map. They are the corners of `bin_to_bounds`.
+    pub fn bin_corners(&self, bin: usize) -> Result<[(f64, f64); 4], IsinError> {
+        let b = self.bin_to_bounds(bin)?;
+
+        Ok([
+            (b.west, b.north),
+            (b.east, b.north),
+            (b.east, b.south),
+            (b.west, b.south),
+        ])
+    }
+
+    /// Convert a single lonlat to a typed bin
+    /// # Arguments
+    /// * `lon` - A longitude value
+    /// * `lat` - A latitude value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let bin = isin.lonlat_to_bin_typed(45.0, 45.0).unwrap();
+    /// let center = isin.bin_to_lonlat_typed(bin).unwrap();
+    /// ```
+    pub fn lonlat_to_bin_typed(&self, lon: f64, lat: f64) -> Result<Bin, IsinError> {
+        self.lonlat_to_bin(lon, lat).map(Bin)
+    }
+
+    /// Convert a typed bin to lonlat, see `bin_to_lonlat`
+    /// # Arguments
+    /// * `bin` - A bin value
+    pub fn bin_to_lonlat_typed(&self, bin: Bin) -> Result<LonLat, IsinError> {
+        self.bin_to_lonlat(bin.0)
+    }
+
+    /// Convert a typed bin to bounds, see `bin_to_bounds`
+    /// # Arguments
+    /// * `bin` - A bin value
+    pub fn bin_to_bounds_typed(&self, bin: Bin) -> Result<Bounds, IsinError> {
+        self.bin_to_bounds(bin.0)
+    }
+
+    /// Get the row of a typed bin, see `row_for_bin`
+    /// # Arguments
+    /// * `bin` - A bin value
+    pub fn row_for_bin_typed(&self, bin: Bin) -> Result<usize, IsinError> {
+        self.row_for_bin(bin.0)
+    }
+
+    /// Convert a bin to its row and column
+    /// # Arguments
+    /// * `bin` - A bin value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// assert_eq!(isin.bin_to_rowcol(5).unwrap(), (1, 1));
+    /// ```
+    /// # Note
+    /// The row is zero-based from south to north, and the column is zero-based from -180 within
+    /// its row.
+    pub fn bin_to_rowcol(&self, bin: usize) -> Result<(usize, usize), IsinError> {
+        let bin = self.internal_bin(bin)?;
+        let row = self.row_of_bin(bin);
+
+        Ok((row, bin - self.basebin[row]))
+    }
+
+    /// Get the row of a bin
+    /// # Arguments
+    /// * `bin` - A bin value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// assert_eq!(isin.row_for_bin(12).unwrap(), 1);
+    /// assert_eq!(isin.row_for_bin(13).unwrap(), 2);
+    /// ```
+    /// # Note
+    /// The row is zero-based from south to north. It is found by a binary search of the first bin
+    /// of each row, the same lookup used by `bin2lonlat` and `bin2bounds`.
+    pub fn row_for_bin(&self, bin: usize) -> Result<usize, IsinError> {
+        let bin = self.internal_bin(bin)?;
+        Ok(self.row_of_bin(bin))
+    }
+
+    /// Convert a row and column to a bin
+    /// # Arguments
+    /// * `row` - A zero-based row, from south to north
+    /// * `col` - A zero-based column within the row, from -180
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// assert_eq!(isin.rowcol_to_bin(1, 1).unwrap(), 5);
+    /// ```
+    pub fn rowcol_to_bin(&self, row: usize, col: usize) -> Result<usize, IsinError> {
+        if row >= self.numrows {
+            return Err(IsinError::InvalidParameter(format!(
+                "row {} is outside of the valid range 0..{}",
+                row, self.numrows
+            )));
+        }
+        if col >= self.numbin[row] {
+            return Err(IsinError::InvalidParameter(format!(
+                "column {} is outside of the valid range 0..{} of row {}",
+                col, self.numbin[row], row
+            )));
+        }
+
+        Ok(self.basebin[row] + col - self.bin_shift())
+    }
+
+    /// Iterate over the centers of every bin of the grid
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// let (bin, lon, lat) = isin.iter_centers().next().unwrap();
+    /// assert_eq!((bin, lon, lat), (1, -120.0, -85.0));
+    /// ```
+    /// # Note
+    /// The items are `(bin, lon, lat)`, computed as the iterator advances, row by row from south
+    /// to north and from west to east within a row.
+    pub fn iter_centers(&self) -> BinCenters<'_> {
+        BinCenters {
+            isin: self,
+            row: 0,
+            col: 0,
+            remaining: self.totbin,
+        }
+    }
+
+    /// Convert lonlat to bin with longitudes in [0, 360]
+    /// # Arguments
+    /// * `lon` - A vector of longitude values in [0, 360]
+    /// * `lat` - A vector of latitude values
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let bin = isin.lonlat2bin_360(&[270.0], &[45.0]).unwrap();
+    /// assert_eq!(bin, isin.lonlat2bin(&[-90.0], &[45.0]).unwrap());
+    /// ```
+    /// # Note
+    /// Longitudes above 180 are shifted to the negative range, see
+    /// `bounds_checker::normalize_longitude`. Both 0 and 360 fall in the bin east of the prime
+    /// meridian and 180 in the last bin of the row, as with `lonlat2bin`.
+    pub fn lonlat2bin_360(&self, lon: &[f64], lat: &[f64]) -> Result<Vec<usize>, IsinError> {
+        if let Some(&bad) = lon.iter().find(|l| !(0.0..=360.0).contains(*l)) {
+            return Err(IsinError::InvalidLongitude {
+                lon: bad,
+                min: 0.0,
+                max: 360.0,
+            });
+        }
+
+        let lon: Vec<f64> = lon.iter().map(|&l| normalize_longitude(l, false)).collect();
+        self.lonlat2bin(&lon, lat)
+    }
+
+    /// Convert bin to lonlat with longitudes in [0, 360]
+    /// # Arguments
+    /// * `bin` - A vector of bin values
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let lonlat = isin.bin2lonlat_360(&[245535]).unwrap();
+    /// assert!(lonlat[0].lon >= 0.0 && lonlat[0].lon < 360.0);
+    /// ```
+    pub fn bin2lonlat_360(&self, bin: &[usize]) -> Result<Vec<LonLat>, IsinError> {
+        Ok(self
+            .bin2lonlat(bin)?
+            .into_iter()
+            .map(|p| LonLat::new(normalize_longitude(p.lon, true), p.lat))
+            .collect())
+    }
+
+    /// Convert lonlat to bin, accepting longitudes just outside of the grid from float noise
+    /// # Arguments
+    /// * `lon` - A vector of longitude values
+    /// * `lat` - A vector of latitude values
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// assert!(isin.lonlat2bin_strict(&[180.0000001], &[0.0]).is_ok());
+    /// assert!(isin.lonlat2bin_strict(&[200.0], &[0.0]).is_err());
+    /// ```
+    /// # Note
+    /// Longitudes within `1e-6` degrees (about 10 cm) of [-180, 180] are clamped onto the edge
+    /// of the grid, further ones are reported as `InvalidLongitude`. `lonlat2bin` rejects any
+    /// longitude outside of the range and `lonlat2bin_clamped` clamps every longitude.
+    pub fn lonlat2bin_strict(&self, lon: &[f64], lat: &[f64]) -> Result<Vec<usize>, IsinError> {
+        const LON_TOLERANCE: f64 = 1e-6;
+
+        check_lengths(lat.len(), lon.len())?;
+
+        lon.iter()
+            .zip(lat.iter())
+            .map(|(&lon, &lat)| {
+                if !(MIN_LON - LON_TOLERANCE..=MAX_LON + LON_TOLERANCE).contains(&lon) {
+                    check_lon(lon)?;
+                }
+                check_lat(lat)?;
+                Ok(self.bin_of(clamp_lon(lon), lat) - self.bin_shift())
+            })
+            .collect()
+    }
+
+    /// Convert lonlat to bin, clamping out-of-range coordinates
+    /// # Arguments
+    /// * `lon` - A vector of longitude values
+    /// * `lat` - A vector of latitude values
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let bin = isin.lonlat2bin_clamped(&[180.0000001], &[-90.0000001]);
+    /// assert_eq!(bin, isin.lonlat2bin(&[180.0], &[-90.0]).unwrap());
+    /// ```
+    /// # Note
+    /// Longitudes are clamped into [-180, 180] and latitudes into [-90, 90], so this never fails.
+    /// Use it for sensor data with floating point noise at the edges of the grid; `lonlat2bin`
+    /// reports such values as errors. Extra values of the longer vector are ignored.
+    pub fn lonlat2bin_clamped(&self, lon: &[f64], lat: &[f64]) -> Vec<usize> {
+        lon.iter()
+            .zip(lat.iter())
+            .map(|(&lon, &lat)| self.bin_of(clamp_lon(lon), clamp_lat(lat)) - self.bin_shift())
+            .collect()
+    }
+
+    /// Convert bin to lonlat
+    /// # Arguments
+    /// * `bin` - A vector of bin values
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let bins = vec![245535, 245536, 247290, 249046, 249047, 250809];
+    /// let lonlat = isin.bin2lonlat(&bins).unwrap();
+    /// println!("Lon: {:?}, lat: {:?}", lonlat[0].lon, lonlat[0].lat);
+    /// ```
+    pub fn bin2lonlat(&self, bin: &[usize]) -> Result<Vec<LonLat>, IsinError> {
+        bin.iter()
+            .map(|&b| Ok(self.lonlat_of(self.internal_bin(b)?)))
+            .collect()
+    }
+
+    /// Convert bin to bounds
+    /// # Arguments
+    /// * `bin` - A vector of bin values
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let bins = vec![245535, 245536, 247290, 249046, 249047, 250809];
+    /// let bounds = isin.bin2bounds(&bins).unwrap();
+    /// println!("Bounds: {:?}", bounds);
+    /// ```
+    pub fn bin2bounds(&self, bin: &[usize]) -> Result<Vec<Bounds>, IsinError> {
+        bin.iter()
+            .map(|&b| Ok(self.bounds_of(self.internal_bin(b)?)))
+            .collect()
+    }
+
+    /// Convert a single lonlat to bin
+    /// # Arguments
+    /// * `lon` - A longitude value
+    /// * `lat` - A latitude value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let bin = isin.lonlat_to_bin(45.0, 45.0).unwrap();
+    /// assert_eq!(bin, isin.lonlat2bin(&[45.0], &[45.0]).unwrap()[0]);
+    /// ```
+    pub fn lonlat_to_bin(&self, lon: f64, lat: f64) -> Result<usize, IsinError> {
+        check_lon(lon)?;
+        check_lat(lat)?;
+
+        Ok(self.bin_of(lon, lat) - self.bin_shift())
+    }
+
+    /// Convert a single bin to lonlat
+    /// # Arguments
+    /// * `bin` - A bin value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let center = isin.bin_to_lonlat(245535).unwrap();
+    /// println!("Lon: {:?}, lat: {:?}", center.lon, center.lat);
+    /// ```
+    pub fn bin_to_lonlat(&self, bin: usize) -> Result<LonLat, IsinError> {
+        let bin = self.internal_bin(bin)?;
+        Ok(self.lonlat_of(bin))
+    }
+
+    /// Convert a single bin to bounds
+    /// # Arguments
+    /// * `bin` - A bin value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let bounds = isin.bin_to_bounds(245535).unwrap();
+    /// println!("Bounds: {:?}", bounds);
+    /// ```
+    pub fn bin_to_bounds(&self, bin: usize) -> Result<Bounds, IsinError> {
+        let bin = self.internal_bin(bin)?;
+        Ok(self.bounds_of(bin))
+    }
+
+    /// Iterate bins in expanding rings around a center bin
+    /// # Arguments
+    /// * `center_bin` - The bin to start from
+    /// * `max_k` - The number of rings to emit around the center bin
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let bins: Vec<usize> = isin.iter_spiral(2963106, 2).unwrap().collect();
+    /// println!("Bins: {:?}", bins);
+    /// ```
+    /// # Note
+    /// Ring `k` holds the bins reached in `k` steps through the 4-connected neighbors (west, east,
+    /// north, south). Each bin is yielded once, the center first, and bins within a ring are sorted.
+    pub fn iter_spiral(
+        &self,
+        center_bin: usize,
+        max_k: usize,
+    ) -> Result<impl Iterator<Item = usize> + '_, IsinError> {
+        self.check_bin(center_bin)?;
+
+        Ok(Spiral {
+            isin: self,
+            visited: HashSet::from([center_bin]),
+            ring: vec![center_bin],
+            pos: 0,
+            k: 0,
+            max_k,
+        })
+    }
+
+    /// Find the bin reached by stepping over the pole from a bin of the top or bottom row
+    /// # Arguments
+    /// * `bin` - A bin value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let bin = isin.transpolar_neighbor(1).unwrap();
+    /// println!("Bin: {:?}", bin);
+    /// ```
+    /// # Note
+    /// The returned bin is the bin of the same row containing the antipodal longitude of the
+    /// center. `None` is returned for bins that are not in a polar row. When the row has an odd
+    /// number of bins, as the 3-bin polar rows of the standard grids, no center is exactly
+    /// antipodal and the mapping is not an involution: with 18 rows, 1 maps to 3, 3 to 2 and 2
+    /// to 1.
+    pub fn transpolar_neighbor(&self, bin: usize) -> Result<Option<usize>, IsinError> {
+        self.check_bin(bin)?;
+
+        let row = self.row_of_bin(bin);
+
+        if row != 0 && row != self.numrows - 1 {
+            return Ok(None);
+        }
+
+        let lon = self.center_lon(row, bin - self.basebin[row]);
+        let antipode = if lon < 0.0 { lon + 180.0 } else { lon - 180.0 };
+
+        Ok(Some(self.basebin[row] + self.col_of_lon(row, antipode)))
+    }
+
+    /// Classify bins with a user supplied land/ocean test
+    /// # Arguments
+    /// * `bins` - A vector of bin values
+    /// * `is_ocean` - A function called with the `(lon, lat)` center of each bin
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let ocean = isin.classify_bins(&[245535, 245536], |lon, _lat| lon < 0.0).unwrap();
+    /// println!("Ocean: {:?}", ocean);
+    /// ```
+    pub fn classify_bins<F: Fn(f64, f64) -> bool>(
+        &self,
+        bins: &[usize],
+        is_ocean: F,
+    ) -> Result<Vec<bool>, IsinError> {
+        self.check_bins(bins)?;
+
+        Ok(bins
+            .iter()
+            .map(|&bin| {
+                let row = self.row_of_bin(bin);
+                is_ocean(
+                    self.center_lon(row, bin - self.basebin[row]),
+                    self.latbin[row],
+                )
+            })
+            .collect())
+    }
+
+    /// Build the 4-connected adjacency of the whole grid in compressed sparse row form
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// let csr = isin.build_adjacency_csr().unwrap();
+    /// println!("Neighbors: {:?}", csr.neighbors(100));
+    /// ```
+    /// # Note
+    /// Neighbors follow `iter_spiral`: west and east wrap around the antimeridian and the polar
+    /// rows have no neighbor beyond the pole. The structure holds about `5 * totbin` integers, which
+    /// is close to 1 GB for MODIS (4320 rows) on 64-bit targets; see
+    /// `build_adjacency_csr_subset` for a region.
+    pub fn build_adjacency_csr(&self) -> Result<AdjacencyCsr, IsinError> {
+        self.build_adjacency_csr_with_progress(None)
+    }
+
+    /// Build the 4-connected adjacency of a subset of the grid in compressed sparse row form
+    /// # Arguments
+    /// * `bins` - A vector of bin values
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// let csr = isin.build_adjacency_csr_subset(&[200, 201, 300]).unwrap();
+    /// assert_eq!(csr.neighbors(200), Some(&[201][..]));
+    /// assert_eq!(csr.neighbors(300), Some(&[][..]));
+    /// ```
+    /// # Note
+    /// The bins are sorted and deduplicated, and only neighbors within the subset are kept. The
+    /// structure holds about `5 * bins.len()` integers plus the sorted bins.
+    pub fn build_adjacency_csr_subset(&self, bins: &[usize]) -> Result<AdjacencyCsr, IsinError> {
+        self.check_bins(bins)?;
+
+        let bins = sorted_set(bins);
+        let mut indptr: Vec<usize> = Vec::with_capacity(bins.len() + 1);
+        let mut indices: Vec<usize> = Vec::new();
+
+        indptr.push(0);
+
+        for &bin in bins.iter() {
+            indices.extend(
+                self.adjacent_bins(bin)
+                    .into_iter()
+                    .filter(|b| bins.binary_search(b).is_ok()),
+            );
+            indptr.push(indices.len());
+        }
+
+        Ok(AdjacencyCsr {
+            indptr,
+            indices,
+            bins: Some(bins),
+        })
+    }
+
+    /// Build the 4-connected adjacency of the whole grid, reporting progress along the way
+    /// # Arguments
+    /// * `progress` - An optional callback receiving the number of bins done and the total
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// let report = |done: usize, total: usize| println!("{}/{}", done, total);
+    /// let csr = isin.build_adjacency_csr_with_progress(Some(&report)).unwrap();
+    /// ```
+    /// # Note
+    /// The callback is invoked once per grid row, after the bins of that row are processed.
+    pub fn build_adjacency_csr_with_progress(
+        &self,
+        progress: Option<&dyn Fn(usize, usize)>,
+    ) -> Result<AdjacencyCsr, IsinError> {
+        self.require_one_based()?;
+
+        let mut indptr: Vec<usize> = Vec::with_capacity(self.totbin + 1);
+        let mut indices: Vec<usize> = Vec::with_capacity(4 * self.totbin);
+
+        indptr.push(0);
+
+        for row in 0..self.numrows {
+            for bin in self.basebin[row]..self.basebin[row] + self.numbin[row] {
+                indices.extend(self.adjacent_bins(bin));
+                indptr.push(indices.len());
+            }
+
+            if let Some(progress) = progress {
+                progress(self.basebin[row] + self.numbin[row] - 1, self.totbin);
+            }
+        }
+
+        Ok(AdjacencyCsr {
+            indptr,
+            indices,
+            bins: None,
+        })
+    }
+
+    /// Compute the extent of a set of bins in (row, col) space
+    /// # Arguments
+    /// * `bins` - A vector of bin values
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let extent = isin.rowcol_extent(&[245535, 245536, 247290]).unwrap();
+    /// println!("Extent: {:?}", extent);
+    /// ```
+    /// # Note
+    /// The extent is returned in the order min_row, max_row, min_col, max_col. Rows are zero-based
+    /// and columns are relative to the start of their own row, so `max_col` does not refer to the
+    /// same longitude in rows of different widths.
+    pub fn rowcol_extent(&self, bins: &[usize]) -> Result<(usize, usize, usize, usize), IsinError> {
+        self.check_bins(bins)?;
+
+        if bins.is_empty() {
+            return Err(IsinError::InvalidParameter(
+                "cannot compute the extent of an empty set of bins".to_string(),
+            ));
+        }
+
+        let mut extent = (usize::MAX, 0, usize::MAX, 0);
+
+        for &bin in bins.iter() {
+            let row = self.row_of_bin(bin);
+            let col = bin - self.basebin[row];
+
+            extent.0 = extent.0.min(row);
+            extent.1 = extent.1.max(row);
+            extent.2 = extent.2.min(col);
+            extent.3 = extent.3.max(col);
+        }
+
+        Ok(extent)
+    }
+
+    /// Check that a list of bins fits within the grid
+    /// # Arguments
+    /// * `bins` - A vector of bin values
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(2160);
+    /// assert!(isin.bins_fit_grid(&[1, 5940422]).is_ok());
+    /// assert!(isin.bins_fit_grid(&[1, 5940423]).is_err());
+    /// ```
+    /// # Note
+    /// The error reports the smallest or largest bin of the list, whichever is out of range. A
+    /// largest bin above the total number of bins usually means the list was produced for a finer
+    /// grid.
+    pub fn bins_fit_grid(&self, bins: &[usize]) -> Result<(), IsinError> {
+        let (min, max) = bins.iter().fold((usize::MAX, 0), |(min, max), &bin| {
+            (min.min(bin), max.max(bin))
+        });
+
+        if bins.is_empty() {
+            return Ok(());
+        }
+
+        self.check_bin(min)?;
+        self.check_bin(max)
+    }
+
+    /// Compute the mean latitude of a set of bins weighted by the cosine of their latitude
+    /// # Arguments
+    /// * `bins` - A vector of bin values
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let lat = isin.cosine_weighted_mean_lat(&[245535, 2963106]).unwrap();
+    /// println!("Lat: {:?}", lat);
+    /// ```
+    /// # Note
+    /// The cosine of the center latitude is proportional to the area of a bin up to the small
+    /// variations caused by rounding the number of bins per row, so this approximates the
+    /// area-weighted mean without computing bin areas.
+    pub fn cosine_weighted_mean_lat(&self, bins: &[usize]) -> Result<f64, IsinError> {
+        self.check_bins(bins)?;
+
+        if bins.is_empty() {
+            return Err(IsinError::InvalidParameter(
+                "cannot compute the mean latitude of an empty set of bins".to_string(),
+            ));
+        }
+
+        let (sum, weight) = bins.iter().fold((0.0, 0.0), |(sum, weight), &bin| {
+            let lat = self.latbin[self.row_of_bin(bin)];
+            let w = f64::cos(lat.to_radians());
+            (sum + w * lat, weight + w)
+        });
+
+        Ok(sum / weight)
+    }
+
+    /// Convert the lonlat of a swath to bins
+    /// # Arguments
+    /// * `lon` - A vector of longitude values
+    /// * `lat` - A vector of latitude values
+    /// * `crosses_dateline` - Whether the swath straddles the ±180 meridian
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let bin = isin.lonlat2bin_swath(&[179.5, 180.5], &[10.0, 10.0], true).unwrap();
+    /// println!("Bin: {:?}", bin);
+    /// ```
+    /// # Note
+    /// Set `crosses_dateline` when the longitudes of the swath were unwrapped to stay contiguous,
+    /// e.g. running from 170 to 190 or from -190 to -170. They are then normalized into [-180, 180)
+    /// before binning, so 180 itself maps to the westernmost column. Without the flag longitudes
+    /// must be within [-180, 180].
+    pub fn lonlat2bin_swath(
+        &self,
+        lon: &[f64],
+        lat: &[f64],
+        crosses_dateline: bool,
+    ) -> Result<Vec<usize>, IsinError> {
+        check_lengths(lat.len(), lon.len())?;
+
+        lon.iter()
+            .zip(lat.iter())
+            .map(|(&lon, &lat)| {
+                let lon = if crosses_dateline && lon.is_finite() {
+                    (lon + 180.0).rem_euclid(360.0) - 180.0
+                } else {
+                    lon
+                };
+
+                check_lon(lon)?;
+                check_lat(lat)?;
+
+                Ok(self.bin_of(lon, lat) - self.bin_shift())
+            })
+            .collect()
+    }
+
+    /// Convert a bin to its center in normalized (u, v) coordinates
+    /// # Arguments
+    /// * `bin` - A bin value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let uv = isin.bin_to_uv(245535).unwrap();
+    /// println!("UV: {:?}", uv);
+    /// ```
+    /// # Note
+    /// `u = (lon + 180) / 360` and `v = (lat + 90) / 180`, both within [0, 1].
+    pub fn bin_to_uv(&self, bin: usize) -> Result<(f64, f64), IsinError> {
+        self.check_bin(bin)?;
+
+        let row = self.row_of_bin(bin);
+        let lon = self.center_lon(row, bin - self.basebin[row]);
+
+        Ok(((lon + 180.0) / 360.0, (self.latbin[row] + 90.0) / 180.0))
+    }
+
+    /// Convert normalized (u, v) coordinates to a bin
+    /// # Arguments
+    /// * `u` - The normalized longitude, clamped to [0, 1]
+    /// * `v` - The normalized latitude, clamped to [0, 1]
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let bin = isin.uv_to_bin(0.5, 0.5).unwrap();
+    /// println!("Bin: {:?}", bin);
+    /// ```
+    pub fn uv_to_bin(&self, u: f64, v: f64) -> Result<usize, IsinError> {
+        self.require_one_based()?;
+
+        let lon = u.clamp(0.0, 1.0) * 360.0 - 180.0;
+        let lat = v.clamp(0.0, 1.0) * 180.0 - 90.0;
+
+        check_lon(lon)?;
+        check_lat(lat)?;
+
+        Ok(self.bin_of(lon, lat))
+    }
+
+    /// Compute the distance from a point to the nearest edge of the bin containing it
+    /// # Arguments
+    /// * `lon` - A longitude value
+    /// * `lat` - A latitude value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let km = isin.distance_to_bin_edge_km(45.0, 45.0).unwrap();
+    /// println!("Distance: {:?}", km);
+    /// ```
+    /// # Note
+    /// The distance to the north and south edges is measured along the meridian, and the distance
+    /// to the west and east edges is the cross-track distance to their meridian, on a sphere of
+    /// radius 6371 km. The point always lies inside its bin, so the result is positive or zero on an
+    /// edge.
+    pub fn distance_to_bin_edge_km(&self, lon: f64, lat: f64) -> Result<f64, IsinError> {
+        check_lon(lon)?;
+        check_lat(lat)?;
+
+        let row = self.row_of_lat(lat);
+        let col = self.col_of_lon(row, lon);
+        let width = 360.0 / self.numbin[row] as f64;
+        let west = col as f64 * width - 180.0;
+        let north = self.latbin[row] + 90.0 / self.numrows as f64;
+        let south = self.latbin[row] - 90.0 / self.numrows as f64;
+
+        let to_meridian = |edge: f64| {
+            let dlon = (lon - edge).to_radians();
+            f64::asin((dlon.sin() * lat.to_radians().cos()).abs().min(1.0))
+        };
+
+        let angle = [
+            (north - lat).abs().to_radians(),
+            (lat - south).abs().to_radians(),
+            to_meridian(west),
+            to_meridian(west + width),
+        ]
+        .into_iter()
+        .fold(f64::INFINITY, f64::min);
+
+        Ok(EARTH_RADIUS_KM * angle)
+    }
+
+    /// Get the bins at the nodes of a regular mesh spanning a bounding box
+    /// # Arguments
+    /// * `west` - The western longitude of the box
+    /// * `east` - The eastern longitude of the box
+    /// * `south` - The southern latitude of the box
+    /// * `north` - The northern latitude of the box
+    /// * `nx` - The number of nodes along longitude
+    /// * `ny` - The number of nodes along latitude
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let bins = isin.bbox_mesh_bins(-70.0, -60.0, 40.0, 50.0, 3, 2).unwrap();
+    /// println!("Bins: {:?}", bins);
+    /// ```
+    /// # Note
+    /// Nodes include the edges of the box, or sit at its middle along an axis with a single node.
+    /// The `nx * ny` bins are returned row-major, starting with the northernmost row of nodes and
+    /// going west to east within each row. A box with `west > east` crosses the antimeridian: the
+    /// nodes step eastward from `west` across 180 to `east`, with their longitudes wrapped into
+    /// [-180, 180].
+    pub fn bbox_mesh_bins(
+        &self,
+        west: f64,
+        east: f64,
+        south: f64,
+        north: f64,
+        nx: usize,
+        ny: usize,
+    ) -> Result<Vec<usize>, IsinError> {
+        self.require_one_based()?;
+        check_lon(west)?;
+        check_lon(east)?;
+        check_lat(south)?;
+        check_lat(north)?;
+
+        if south > north {
+            return Err(IsinError::InvalidParameter(format!(
+                "invalid bounding box west {}, east {}, south {}, north {}",
+                west, east, south, north
+            )));
+        }
+
+        if nx == 0 || ny == 0 {
+            return Err(IsinError::InvalidParameter(format!(
+                "mesh dimensions must be positive, got {}x{}",
+                nx, ny
+            )));
+        }
+
+        let east = if west > east { east + 360.0 } else { east };
+
+        let node = |start: f64, end: f64, i: usize, n: usize| {
+            if n == 1 {
+                (start + end) / 2.0
+            } else {
+                start + (end - start) * i as f64 / (n - 1) as f64
+            }
+        };
+
+        let mut bins: Vec<usize> = Vec::with_capacity(nx * ny);
+
+        for j in 0..ny {
+            let lat = node(north, south, j, ny);
+            for i in 0..nx {
+                let lon = normalize_longitude(node(west, east, i, nx), false);
+                bins.push(self.bin_of(lon, lat));
+            }
+        }
+
+        Ok(bins)
+    }
+
+    /// Get the center coordinates of the grid for CF coordinate variables
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// let (lat, lon) = isin.cf_coordinates();
+    /// println!("Lat: {:?}, Lon: {:?}", lat[0], lon[0]);
+    /// ```
+    /// # Note
+    /// The first vector holds the `numrows` row center latitudes from south to north and the second
+    /// holds, per row, the center longitudes of its bins from west to east. The rows have different
+    /// lengths, so this is a ragged representation rather than a rectangular lat/lon grid.
+    pub fn cf_coordinates(&self) -> (Vec<f64>, Vec<Vec<f64>>) {
+        self.cf_coordinates_with_progress(None)
+    }
+
+    /// Get the center coordinates of the grid for CF coordinate variables, reporting progress
+    /// # Arguments
+    /// * `progress` - An optional callback receiving the number of bins done and the total
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// let report = |done: usize, total: usize| println!("{}/{}", done, total);
+    /// let (lat, lon) = isin.cf_coordinates_with_progress(Some(&report));
+    /// ```
+    /// # Note
+    /// The callback is invoked once per grid row, after the centers of that row are computed. See
+    /// `cf_coordinates` for the layout of the coordinates.
+    pub fn cf_coordinates_with_progress(
+        &self,
+        progress: Option<&dyn Fn(usize, usize)>,
+    ) -> (Vec<f64>, Vec<Vec<f64>>) {
+        let lon = (0..self.numrows)
+            .map(|row| {
+                let centers = (0..self.numbin[row])
+                    .map(|col| self.center_lon(row, col))
+                    .collect();
+
+                if let Some(progress) = progress {
+                    progress(self.basebin[row] + self.numbin[row] - 1, self.totbin);
+                }
+
+                centers
+            })
+            .collect();
+
+        (self.latbin.clone(), lon)
+    }
+
+    /// Check that the grid arrays are consistent with each other
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// assert!(isin.check_invariants().is_ok());
+    /// ```
+    /// # Note
+    /// The row lookups rely on `basebin` starting at 1 and each row starting right after the
+    /// previous one, on strictly increasing `latbin`, and on `totbin` ending the last row.
+    pub fn check_invariants(&self) -> Result<(), IsinError> {
+        let invalid = |msg: &str| Err(IsinError::InvalidGrid(msg.to_string()));
+
+        if self.numrows == 0
+            || self.basebin.len() != self.numrows
+            || self.numbin.len() != self.numrows
+            || self.latbin.len() != self.numrows
+            || self.colscale.len() != self.numrows
+        {
+            return invalid("the row arrays do not hold numrows values");
+        }
+
+        if self.basebin[0] != 1 {
+            return invalid("basebin does not start at 1");
+        }
+
+        if self.numbin.contains(&0) {
+            return invalid("a row holds no bins");
+        }
+
+        for row in 1..self.numrows {
+            if self.basebin[row] != self.basebin[row - 1] + self.numbin[row - 1] {
+                return invalid("a row does not start right after the previous one");
+            }
+
+            if self.latbin[row] <= self.latbin[row - 1] {
+                return invalid("latbin is not strictly increasing");
+            }
+        }
+
+        if self.totbin != self.basebin[self.numrows - 1] + self.numbin[self.numrows - 1] - 1 {
+            return invalid("totbin does not end the last row");
+        }
+
+        Ok(())
+    }
+
+    /// Pack a binned field into `u16` values with a scale and offset
+    /// # Arguments
+    /// * `values` - One optional value per bin, indexed by `bin - 1`
+    /// * `scale` - The value represented by one packed unit
+    /// * `offset` - The value represented by a packed 0
+    /// * `fill` - The packed value used for missing bins
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// let values = vec![Some(1.5); 412];
+    /// let packed = isin.pack_values_u16(&values, 0.01, 0.0, u16::MAX).unwrap();
+    /// println!("Packed: {:?}", packed[0]);
+    /// ```
+    /// # Note
+    /// Values are packed as `round((v - offset) / scale)` clamped to the `u16` range, so a value
+    /// clamped onto `fill` reads back as missing. `NaN` values are packed as `fill`.
+    pub fn pack_values_u16(
+        &self,
+        values: &[Option<f64>],
+        scale: f64,
+        offset: f64,
+        fill: u16,
+    ) -> Result<Vec<u16>, IsinError> {
+        check_lengths(self.totbin, values.len())?;
+        check_scale(scale)?;
+
+        Ok(values
+            .iter()
+            .map(|value| match value {
+                Some(v) if !v.is_nan() => ((v - offset) / scale)
+                    .round()
+                    .clamp(u16::MIN as f64, u16::MAX as f64)
+                    as u16,
+                _ => fill,
+            })
+            .collect())
+    }
+
+    /// Unpack `u16` values produced by `pack_values_u16`
+    /// # Arguments
+    /// * `packed` - One packed value per bin, indexed by `bin - 1`
+    /// * `scale` - The value represented by one packed unit
+    /// * `offset` - The value represented by a packed 0
+    /// * `fill` - The packed value used for missing bins
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// let packed = vec![150; 412];
+    /// let values = isin.unpack_values_u16(&packed, 0.01, 0.0, u16::MAX).unwrap();
+    /// println!("Value: {:?}", values[0]);
+    /// ```
+    pub fn unpack_values_u16(
+        &self,
+        packed: &[u16],
+        scale: f64,
+        offset: f64,
+        fill: u16,
+    ) -> Result<Vec<Option<f64>>, IsinError> {
+        check_lengths(self.totbin, packed.len())?;
+        check_scale(scale)?;
+
+        Ok(packed
+            .iter()
+            .map(|&p| (p != fill).then_some(p as f64 * scale + offset))
+            .collect())
+    }
+
+    /// Get the bins of the grid row under a scanline of an equirectangular image
+    /// # Arguments
+    /// * `out_height` - The height of the image in pixels
+    /// * `j` - The image row, 0 being the northernmost
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let bins = isin.bins_for_image_row(1800, 900).unwrap();
+    /// println!("Bins: {:?}", bins.len());
+    /// ```
+    /// # Note
+    /// The grid row is the one containing the latitude of the center of image row `j`.
+    pub fn bins_for_image_row(&self, out_height: usize, j: usize) -> Result<Vec<usize>, IsinError> {
+        self.require_one_based()?;
+
+        if j >= out_height {
+            return Err(IsinError::InvalidParameter(format!(
+                "image row {} is outside of an image of height {}",
+                j, out_height
+            )));
+        }
+
+        let lat = 90.0 - (j as f64 + 0.5) * 180.0 / out_height as f64;
+        let row = self.row_of_lat(lat);
+
+        Ok((self.basebin[row]..self.basebin[row] + self.numbin[row]).collect())
+    }
+
+    /// Run-length encode the coverage of a set of bins row by row
+    /// # Arguments
+    /// * `bins` - A vector of bin values
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// let rle = isin.coverage_rle(&[2, 3, 5]).unwrap();
+    /// println!("First row: {:?}", rle[0]);
+    /// ```
+    /// # Note
+    /// The outer vector holds one entry per row, from south to north. Each row is a list of
+    /// `(present, length)` runs going west to east whose lengths add up to the number of bins of the
+    /// row. Duplicate bins are counted once.
+    pub fn coverage_rle(&self, bins: &[usize]) -> Result<Vec<Vec<(bool, usize)>>, IsinError> {
+        self.check_bins(bins)?;
+
+        let mut sorted = bins.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+
+        let mut present = sorted.iter().peekable();
+        let mut rle: Vec<Vec<(bool, usize)>> = Vec::with_capacity(self.numrows);
+
+        for row in 0..self.numrows {
+            let mut runs: Vec<(bool, usize)> = Vec::new();
+
+            for bin in self.basebin[row]..self.basebin[row] + self.numbin[row] {
+                let is_present = present.next_if_eq(&&bin).is_some();
+
+                match runs.last_mut() {
+                    Some((flag, len)) if *flag == is_present => *len += 1,
+                    _ => runs.push((is_present, 1)),
+                }
+            }
+
+            rle.push(runs);
+        }
+
+        Ok(rle)
+    }
+
+    /// Get bins and their WKT polygons as parallel columns, e.g. for a GeoParquet writer
+    /// # Arguments
+    /// * `bins` - A vector of bin values
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let (bins, wkt) = isin.to_geometry_columns(&[245535, 245536]).unwrap();
+    /// println!("{}: {}", bins[0], wkt[0]);
+    /// ```
+    /// # Note
+    /// The first column of every row starts exactly at -180 and the last one ends exactly at 180,
+    /// so no cell straddles the antimeridian and every geometry is a single valid polygon.
+    pub fn to_geometry_columns(
+        &self,
+        bins: &[usize],
+    ) -> Result<(Vec<usize>, Vec<String>), IsinError> {
+        self.check_bins(bins)?;
+
+        Ok((
+            bins.to_vec(),
+            bins.iter().map(|&bin| self.wkt_of(bin)).collect(),
+        ))
+    }
+
+    /// Find the bins whose value satisfies a comparison with a threshold
+    /// # Arguments
+    /// * `values` - One optional value per bin, indexed by `bin - 1`
+    /// * `op` - The comparison to apply
+    /// * `threshold` - The value to compare with
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// let values: Vec<Option<f64>> = (0..412).map(|i| Some(i as f64)).collect();
+    /// let bins = isin.threshold_bins(&values, l3bin::CmpOp::Gt, 410.0).unwrap();
+    /// assert_eq!(bins, vec![412]);
+    /// ```
+    /// # Note
+    /// Missing values never satisfy the comparison. The returned bins are sorted.
+    pub fn threshold_bins(
+        &self,
+        values: &[Option<f64>],
+        op: CmpOp,
+        threshold: f64,
+    ) -> Result<Vec<usize>, IsinError> {
+        self.require_one_based()?;
+        check_lengths(self.totbin, values.len())?;
+
+        Ok(values
+            .iter()
+            .enumerate()
+            .filter_map(|(i, value)| {
+                let v = (*value)?;
+                let keep = match op {
+                    CmpOp::Gt => v > threshold,
+                    CmpOp::Ge => v >= threshold,
+                    CmpOp::Lt => v < threshold,
+                    CmpOp::Le => v <= threshold,
+                };
+                keep.then_some(i + 1)
+            })
+            .collect())
+    }
+
+    /// Get the longitude width of the bins of each row
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let resolution = isin.row_lon_resolution_deg();
+    /// println!("Equator: {:?} degrees", resolution[2160]);
+    /// ```
+    /// # Note
+    /// The widths are in degrees, from the southernmost row to the northernmost. They are smallest
+    /// near the equator and largest in the polar rows.
+    pub fn row_lon_resolution_deg(&self) -> Vec<f64> {
+        self.numbin.iter().map(|&n| 360.0 / n as f64).collect()
+    }
+
+    /// Convert lonlat sorted by latitude to bins
+    /// # Arguments
+    /// * `lon` - A vector of longitude values
+    /// * `lat` - A vector of latitude values in non-decreasing order
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let bin = isin.lonlat2bin_sorted_by_lat(&[10.0, -20.0], &[44.0, 45.0]).unwrap();
+    /// println!("Bin: {:?}", bin);
+    /// ```
+    /// # Note
+    /// The row is found by advancing a cursor from the previous point instead of being computed
+    /// from scratch, which suits scanline-ordered swaths. An `UnsortedLatitude` error is returned
+    /// as soon as a latitude is smaller than the previous one.
+    pub fn lonlat2bin_sorted_by_lat(
+        &self,
+        lon: &[f64],
+        lat: &[f64],
+    ) -> Result<Vec<usize>, IsinError> {
+        check_lengths(lat.len(), lon.len())?;
+
+        let mut bins: Vec<usize> = Vec::with_capacity(lat.len());
+        let mut row = 0;
+        let mut previous = MIN_LAT;
+
+        for (i, (&lon, &lat)) in lon.iter().zip(lat.iter()).enumerate() {
+            check_lon(lon)?;
+            check_lat(lat)?;
+
+            if lat < previous {
+                return Err(IsinError::UnsortedLatitude { index: i });
+            }
+            previous = lat;
+
+            let pos = (90.0 + lat) * (self.numrows as f64) / 180.0;
+            while row + 1 < self.numrows && pos >= (row + 1) as f64 {
+                row += 1;
+            }
+
+            bins.push(self.basebin[row] + self.col_of_lon(row, lon) - self.bin_shift());
+        }
+
+        Ok(bins)
+    }
+
+    /// Compute the spherical convex hull of the centers of a set of bins
+    /// # Arguments
+    /// * `bins` - A vector of bin values
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let hull = isin.bins_convex_hull(&[245535, 245536, 247290, 249046]).unwrap();
+    /// println!("Hull: {:?}", hull);
+    /// ```
+    /// # Note
+    /// The centers are projected with a gnomonic projection around their mean direction, in which
+    /// great circles are straight lines, so the planar hull is the spherical hull. This requires the
+    /// centers to lie within the hemisphere around their mean, otherwise an error is returned. The
+    /// (lon, lat) vertices are returned counter-clockwise without repeating the first one, and the
+    /// antimeridian needs no special handling.
+    pub fn bins_convex_hull(&self, bins: &[usize]) -> Result<Vec<(f64, f64)>, IsinError> {
+        self.check_bins(bins)?;
+
+        let to_xyz = |lon: f64, lat: f64| {
+            let (lon, lat) = (lon.to_radians(), lat.to_radians());
+            [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()]
+        };
+        let dot = |a: [f64; 3], b: [f64; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+
+        let centers: Vec<[f64; 3]> = bins
+            .iter()
+            .map(|&bin| {
+                let row = self.row_of_bin(bin);
+                to_xyz(
+                    self.center_lon(row, bin - self.basebin[row]),
+                    self.latbin[row],
+                )
+            })
+            .collect();
+
+        let sum = centers.iter().fold([0.0; 3], |acc, p| {
+            [acc[0] + p[0], acc[1] + p[1], acc[2] + p[2]]
+        });
+        let norm = dot(sum, sum).sqrt();
+
+        if norm < 1e-9 {
+            return Err(IsinError::InvalidParameter(
+                "the bins are empty or spread over the whole sphere".to_string(),
+            ));
+        }
+
+        let c = [sum[0] / norm, sum[1] / norm, sum[2] / norm];
+        let (lon0, lat0) = (f64::atan2(c[1], c[0]), c[2].asin());
+        let east = [-lon0.sin(), lon0.cos(), 0.0];
+        let north = [
+            -lat0.sin() * lon0.cos(),
+            -lat0.sin() * lon0.sin(),
+            lat0.cos(),
+        ];
+
+        let mut points: Vec<(f64, f64)> = Vec::with_capacity(centers.len());
+
+        for p in centers.iter() {
+            let d = dot(*p, c);
+            if d <= 1e-9 {
+                return Err(IsinError::InvalidParameter(
+                    "the bins do not fit in a hemisphere".to_string(),
+                ));
+            }
+            points.push((dot(*p, east) / d, dot(*p, north) / d));
+        }
+
+        points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        points.dedup();
+
+        let cross = |o: (f64, f64), a: (f64, f64), b: (f64, f64)| {
+            (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+        };
+
+        // Andrew's monotone chain, lower then upper half
+        let half = |points: &mut dyn Iterator<Item = &(f64, f64)>| {
+            let mut chain: Vec<(f64, f64)> = Vec::new();
+            for &p in points {
+                while chain.len() >= 2
+                    && cross(chain[chain.len() - 2], chain[chain.len() - 1], p) <= 0.0
+                {
+                    chain.pop();
+                }
+                chain.push(p);
+            }
+            chain.pop();
+            chain
+        };
+
+        let mut hull = half(&mut points.iter());
+        hull.extend(half(&mut points.iter().rev()));
+
+        if hull.is_empty() {
+            hull = points;
+        }
+
+        Ok(hull
+            .iter()
+            .map(|&(x, y)| {
+                let p = [
+                    c[0] + x * east[0] + y * north[0],
+                    c[1] + x * east[1] + y * north[1],
+                    c[2] + x * east[2] + y * north[2],
+                ];
+                let norm = dot(p, p).sqrt();
+                (
+                    f64::atan2(p[1], p[0]).to_degrees(),
+                    (p[2] / norm).asin().to_degrees(),
+                )
+            })
+            .collect())
+    }
+
+    /// Merge 4-connected bins sharing the same value into polygons
+    /// # Arguments
+    /// * `values` - One optional value per bin, indexed by `bin - 1`
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// let values = vec![Some(1.0); 412];
+    /// let polygons = isin.dissolve_by_value(&values).unwrap();
+    /// println!("Polygons: {:?}", polygons.len());
+    /// ```
+    /// # Note
+    /// Each entry is a `(value, ring)` pair where the ring is a closed list of (lon, lat) vertices.
+    /// Outer boundaries run counter-clockwise and holes run clockwise, each hole being returned as
+    /// its own entry. Values are compared exactly and missing or `NaN` values are left out. Regions
+    /// are cut at the antimeridian so that every ring stays within [-180, 180].
+    pub fn dissolve_by_value(&self, values: &[Option<f64>]) -> Result<Vec<(f64, Ring)>, IsinError> {
+        check_lengths(self.totbin, values.len())?;
+
+        // Vertices are (row boundary, numerator, denominator) with the longitude as a reduced
+        // fraction of the full circle, so that edges coming from rows of different widths meet
+        // exactly.
+        type Vertex = (usize, u64, u64);
+
+        let key = |bin: usize| match values[bin - 1] {
+            Some(v) if !v.is_nan() => Some((v + 0.0).to_bits()),
+            _ => None,
+        };
+        let vertex = |k: usize, num: usize, den: usize| {
+            let g = gcd(num as u64, den as u64);
+            (k, num as u64 / g, den as u64 / g)
+        };
+        let less = |a: (u64, u64), b: (u64, u64)| {
+            (a.0 as u128 * b.1 as u128) < (b.0 as u128 * a.1 as u128)
+        };
+
+        let mut groups: Vec<(u64, Vec<(Vertex, Vertex)>)> = Vec::new();
+        let mut group_of: HashMap<u64, usize> = HashMap::new();
+
+        for row in 0..self.numrows {
+            let n = self.numbin[row];
+
+            for col in 0..n {
+                let bin = self.basebin[row] + col;
+                let Some(v) = key(bin) else { continue };
+                let g = *group_of.entry(v).or_insert_with(|| {
+                    groups.push((v, Vec::new()));
+                    groups.len() - 1
+                });
+                let edges = &mut groups[g].1;
+
+                // Bottom edge runs west to east and top edge east to west, minus the parts shared
+                // with same-valued bins of the adjacent row
+                for (k, other) in [(row, row.checked_sub(1)), (row + 1, Some(row + 1))] {
+                    let start = (col as u64, n as u64);
+                    let end = (col as u64 + 1, n as u64);
+                    let mut pieces: Vec<((u64, u64), (u64, u64))> = Vec::new();
+                    let mut cursor = start;
+
+                    if let Some(other) = other.filter(|&o| o < self.numrows) {
+                        let m = self.numbin[other];
+                        let first = col * m / n;
+                        let last = ((col + 1) * m).div_ceil(n);
+
+                        for j in first..last.min(m) {
+                            if key(self.basebin[other] + j) != Some(v) {
+                                continue;
+                            }
+                            let lo = (j as u64, m as u64);
+                            let hi = (j as u64 + 1, m as u64);
+                            let lo = if less(lo, start) { start } else { lo };
+                            let hi = if less(end, hi) { end } else { hi };
+
+                            if less(cursor, lo) {
+                                pieces.push((cursor, lo));
+                            }
+                            if less(cursor, hi) {
+                                cursor = hi;
+                            }
+                        }
+                    }
+
+                    if less(cursor, end) {
+                        pieces.push((cursor, end));
+                    }
+
+                    for (lo, hi) in pieces {
+                        let lo = vertex(k, lo.0 as usize, lo.1 as usize);
+                        let hi = vertex(k, hi.0 as usize, hi.1 as usize);
+                        edges.push(if k == row { (lo, hi) } else { (hi, lo) });
+                    }
+                }
+
+                // West edge runs north to south and east edge south to north
+                if col == 0 || key(bin - 1) != Some(v) {
+                    edges.push((vertex(row + 1, col, n), vertex(row, col, n)));
+                }
+                if col + 1 == n || key(bin + 1) != Some(v) {
+                    edges.push((vertex(row, col + 1, n), vertex(row + 1, col + 1, n)));
+                }
+            }
+        }
+
+        let to_lonlat = |p: &Vertex| {
+            (
+                360.0 * p.1 as f64 / p.2 as f64 - 180.0,
+                p.0 as f64 * 180.0 / self.numrows as f64 - 90.0,
+            )
+        };
+
+        let mut polygons: Vec<(f64, Ring)> = Vec::new();
+
+        for (v, edges) in groups.iter() {
+            let mut outgoing: HashMap<Vertex, Vec<usize>> = HashMap::new();
+            for (i, edge) in edges.iter().enumerate() {
+                outgoing.entry(edge.0).or_default().push(i);
+            }
+
+            let mut used = vec![false; edges.len()];
+
+            for first in 0..edges.len() {
+                if used[first] {
+                    continue;
+                }
+
+                let mut ring: Vec<Vertex> = Vec::new();
+                let mut current = first;
+
+                loop {
+                    used[current] = true;
+                    ring.push(edges[current].0);
+
+                    let next = outgoing.get_mut(&edges[current].1).and_then(|candidates| {
+                        candidates.retain(|&i| !used[i]);
+                        candidates.pop()
+                    });
+
+                    match next {
+                        Some(next) => current = next,
+                        None => break,
+                    }
+                }
+
+                // Drop the vertices in the middle of straight horizontal or vertical runs
+                let len = ring.len();
+                let corners: Vec<&Vertex> = (0..len)
+                    .filter(|&i| {
+                        let (prev, cur, next) =
+                            (&ring[(i + len - 1) % len], &ring[i], &ring[(i + 1) % len]);
+                        !((prev.0 == cur.0 && cur.0 == next.0)
+                            || ((prev.1, prev.2) == (cur.1, cur.2)
+                                && (cur.1, cur.2) == (next.1, next.2)))
+                    })
+                    .map(|i| &ring[i])
+                    .collect();
+
+                let mut outline: Vec<(f64, f64)> = corners.iter().map(|p| to_lonlat(p)).collect();
+                outline.push(outline[0]);
+                polygons.push((f64::from_bits(*v), outline));
+            }
+        }
+
+        Ok(polygons)
+    }
+
+    /// Get the area weight of each row for global integrals and spectral transforms
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(2160);
+    /// let weights = isin.row_quadrature_weights();
+    /// println!("Equator: {:?}", weights[1080]);
+    /// ```
+    /// # Note
+    /// Each weight is proportional to `sin(north) - sin(south)`, the area of the latitude band of
+    /// the row, and the weights are normalized to sum to 1 so that a global mean is the weighted
+    /// sum of the row means.
+    pub fn row_quadrature_weights(&self) -> Vec<f64> {
+        let half = 90.0 / self.numrows as f64;
+        let bands: Vec<f64> = self
+            .latbin
+            .iter()
+            .map(|&lat| f64::sin((lat + half).to_radians()) - f64::sin((lat - half).to_radians()))
+            .collect();
+        let total: f64 = bands.iter().sum();
+
+        bands.iter().map(|&band| band / total).collect()
+    }
+
+    /// Convert lonlat to bins, rejecting coordinates that look like radians
+    /// # Arguments
+    /// * `lon` - A vector of longitude values
+    /// * `lat` - A vector of latitude values
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// assert!(isin.lonlat2bin_checked(&[-60.0, 45.0], &[10.0, 45.0]).is_ok());
+    /// assert!(isin.lonlat2bin_checked(&[-1.05, 0.79], &[0.17, 0.79]).is_err());
+    /// ```
+    /// # Note
+    /// A `SuspectedRadians` error is returned when every longitude is within [-π, π], every
+    /// latitude within [-π/2, π/2] and the longitudes spread over at least 1 unit. In degrees such
+    /// points would all fall in a few degrees around (0, 0) while covering a sizable part of that
+    /// box, which is far more likely to be radians. Genuine data in that area can use `lonlat2bin`.
+    pub fn lonlat2bin_checked(&self, lon: &[f64], lat: &[f64]) -> Result<Vec<usize>, IsinError> {
+        check_lengths(lat.len(), lon.len())?;
+
+        let in_radians = lon.iter().all(|l| l.abs() <= std::f64::consts::PI)
+            && lat.iter().all(|l| l.abs() <= std::f64::consts::FRAC_PI_2);
+        let spread = lon.iter().cloned().fold(f64::NEG_INFINITY, f64::max)
+            - lon.iter().cloned().fold(f64::INFINITY, f64::min);
+
+        if in_radians && spread >= 1.0 {
+            return Err(IsinError::SuspectedRadians);
+        }
+
+        lon.iter()
+            .zip(lat.iter())
+            .map(|(&lon, &lat)| {
+                check_lon(lon)?;
+                check_lat(lat)?;
+                Ok(self.bin_of(lon, lat) - self.bin_shift())
+            })
+            .collect()
+    }
+
+    /// Count the neighbors of a bin
+    /// # Arguments
+    /// * `bin` - A bin value
+    /// * `connectivity` - Which bins of the adjacent rows count as neighbors
+    /// # Example
+    /// ```
+    /// use l3bin::{Connectivity, Isin};
+    /// let isin = Isin::new(4320);
+    /// let degree = isin.neighbor_degree(2963106, Connectivity::Four).unwrap();
+    /// assert_eq!(degree, 4);
+    /// ```
+    /// # Note
+    /// West and east neighbors wrap around the antimeridian, so bins there have the same degree as
+    /// elsewhere in their row. Bins of the polar rows have no neighbor beyond the pole, and rows of
+    /// one or two bins have fewer west and east neighbors.
+    pub fn neighbor_degree(
+        &self,
+        bin: usize,
+        connectivity: Connectivity,
+    ) -> Result<usize, IsinError> {
+        self.check_bin(bin)?;
+
+        Ok(self.connected_bins(bin, connectivity).len())
+    }
+
+    /// Count the neighbors of several bins
+    /// # Arguments
+    /// * `bins` - A vector of bin values
+    /// * `connectivity` - Which bins of the adjacent rows count as neighbors
+    /// # Example
+    /// ```
+    /// use l3bin::{Connectivity, Isin};
+    /// let isin = Isin::new(4320);
+    /// let degrees = isin.neighbor_degrees(&[1, 2963106], Connectivity::Eight).unwrap();
+    /// println!("Degrees: {:?}", degrees);
+    /// ```
+    pub fn neighbor_degrees(
+        &self,
+        bins: &[usize],
+        connectivity: Connectivity,
+    ) -> Result<Vec<usize>, IsinError> {
+        self.check_bins(bins)?;
+
+        Ok(bins
+            .iter()
+            .map(|&bin| self.connected_bins(bin, connectivity).len())
+            .collect())
+    }
+
+    /// Pack a bin and its row into a single 64-bit record
+    /// # Arguments
+    /// * `bin` - A bin value
+    /// * `row` - The zero-based row of the bin
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let packed = isin.pack_bin_record(245535, 280).unwrap();
+    /// assert_eq!(isin.unpack_bin_record(packed).unwrap(), (245535, 280));
+    /// ```
+    /// # Note
+    /// OBPG products do not share a single documented packed layout, so this uses the row in the
+    /// upper 32 bits and the bin in the lower 32 bits, which holds every bin of grids up to about
+    /// 58000 rows. The row must be the one containing the bin.
+    pub fn pack_bin_record(&self, bin: usize, row: usize) -> Result<u64, IsinError> {
+        self.check_record(bin, row)?;
+
+        Ok(((row as u64) << 32) | bin as u64)
+    }
+
+    /// Unpack a 64-bit record produced by `pack_bin_record` into its bin and row
+    /// # Arguments
+    /// * `packed` - The packed record
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let (bin, row) = isin.unpack_bin_record((280 << 32) | 245535).unwrap();
+    /// println!("Bin: {}, Row: {}", bin, row);
+    /// ```
+    pub fn unpack_bin_record(&self, packed: u64) -> Result<(usize, usize), IsinError> {
+        let bin = (packed & 0xFFFF_FFFF) as usize;
+        let row = (packed >> 32) as usize;
+
+        self.check_record(bin, row)?;
+
+        Ok((bin, row))
+    }
+
+    /// Keep the bins whose center has the sun above the horizon at a given time
+    /// # Arguments
+    /// * `bins` - A vector of bin values
+    /// * `datetime_utc` - The time as a UNIX timestamp in seconds
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(2160);
+    /// // 2024-06-21 12:00:00 UTC
+    /// let day = isin.sunlit_bins(&[1, 5940422], 1718971200).unwrap();
+    /// assert_eq!(day, vec![5940422]);
+    /// ```
+    /// # Note
+    /// The subsolar point comes from the low precision solar coordinates of the Astronomical
+    /// Almanac (about 0.01 degree accuracy between 1950 and 2050) and Greenwich mean sidereal time.
+    /// A bin is sunlit when its center has a positive geometric solar elevation, without
+    /// atmospheric refraction.
+    pub fn sunlit_bins(&self, bins: &[usize], datetime_utc: i64) -> Result<Vec<usize>, IsinError> {
+        self.check_bins(bins)?;
+
+        // Days since J2000.0 (2000-01-01 12:00 UTC)
+        let d = (datetime_utc - 946_728_000) as f64 / 86400.0;
+        let mean_lon = 280.460 + 0.9856474 * d;
+        let anomaly = (357.528 + 0.9856003 * d).to_radians();
+        let ecliptic_lon =
+            (mean_lon + 1.915 * anomaly.sin() + 0.020 * (2.0 * anomaly).sin()).to_radians();
+        let obliquity = (23.439 - 0.0000004 * d).to_radians();
+
+        let declination = f64::asin(obliquity.sin() * ecliptic_lon.sin());
+        let right_ascension = f64::atan2(obliquity.cos() * ecliptic_lon.sin(), ecliptic_lon.cos());
+        let gmst = (280.46061837 + 360.98564736629 * d).to_radians();
+        let subsolar_lon = right_ascension - gmst;
+
+        Ok(bins
+            .iter()
+            .copied()
+            .filter(|&bin| {
+                let row = self.row_of_bin(bin);
+                let lon = self.center_lon(row, bin - self.basebin[row]).to_radians();
+                let lat = self.latbin[row].to_radians();
+
+                let elevation = lat.sin() * declination.sin()
+                    + lat.cos() * declination.cos() * (lon - subsolar_lon).cos();
+                elevation > 0.0
+            })
+            .collect())
+    }
+
+    /// Get the bins crossed by a parallel
+    /// # Arguments
+    /// * `lat` - A latitude value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// let bins = isin.bins_on_parallel(-85.0).unwrap();
+    /// assert_eq!(bins, vec![1, 2, 3]);
+    /// ```
+    /// # Note
+    /// All the bins of the row containing the latitude are returned, from west to east.
+    pub fn bins_on_parallel(&self, lat: f64) -> Result<Vec<usize>, IsinError> {
+        self.require_one_based()?;
+        check_lat(lat)?;
+
+        let row = self.row_of_lat(lat);
+        Ok((self.basebin[row]..self.basebin[row] + self.numbin[row]).collect())
+    }
+
+    /// Get the bins crossed by a meridian
+    /// # Arguments
+    /// * `lon` - A longitude value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// let bins = isin.bins_on_meridian(0.0).unwrap();
+    /// assert_eq!(bins.len(), 18);
+    /// assert_eq!(bins[0], 2);
+    /// ```
+    /// # Note
+    /// One bin per row is returned, from the southernmost row to the northernmost.
+    pub fn bins_on_meridian(&self, lon: f64) -> Result<Vec<usize>, IsinError> {
+        self.require_one_based()?;
+        check_lon(lon)?;
+
+        Ok((0..self.numrows)
+            .map(|row| self.basebin[row] + self.col_of_lon(row, lon))
+            .collect())
+    }
+
+    /// Compute the length of the edge shared by two bins
+    /// # Arguments
+    /// * `a` - A bin value
+    /// * `b` - A bin value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let km = isin.shared_edge_length_km(245535, 245536).unwrap();
+    /// println!("Edge: {:?}", km);
+    /// ```
+    /// # Note
+    /// Bins of the same row share a meridian segment as tall as the row. Bins of adjacent rows
+    /// share the part of the parallel between them where their longitude extents overlap, measured
+    /// along that parallel. Bins that only touch at a corner, or do not touch at all, share no edge
+    /// and give 0. Lengths are on a sphere of radius 6371 km.
+    pub fn shared_edge_length_km(&self, a: usize, b: usize) -> Result<f64, IsinError> {
+        self.check_bin(a)?;
+        self.check_bin(b)?;
+
+        let (row_a, row_b) = (self.row_of_bin(a), self.row_of_bin(b));
+
+        if row_a == row_b {
+            let n = self.numbin[row_a];
+            let (col_a, col_b) = (a - self.basebin[row_a], b - self.basebin[row_b]);
+            if a != b && ((col_a + 1) % n == col_b || (col_b + 1) % n == col_a) {
+                return Ok(EARTH_RADIUS_KM * (180.0 / self.numrows as f64).to_radians());
+            }
+            return Ok(0.0);
+        }
+
+        if row_a.abs_diff(row_b) != 1 {
+            return Ok(0.0);
+        }
+
+        let (bounds_a, bounds_b) = (self.bounds_of(a), self.bounds_of(b));
+        let overlap = bounds_a.east.min(bounds_b.east) - bounds_a.west.max(bounds_b.west);
+        if overlap <= 0.0 {
+            return Ok(0.0);
+        }
+
+        let edge = if row_a < row_b {
+            bounds_b.south
+        } else {
+            bounds_b.north
+        };
+        Ok(EARTH_RADIUS_KM * edge.to_radians().cos() * overlap.to_radians())
+    }
+
+    /// Get the bins whose center falls inside a bounding box
+    /// # Arguments
+    /// * `north` - The northern latitude of the box
+    /// * `south` - The southern latitude of the box
+    /// * `west` - The western longitude of the box
+    /// * `east` - The eastern longitude of the box
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let bins = isin.bins_in_bbox(50.0, 40.0, -70.0, -60.0).unwrap();
+    /// println!("Bins: {:?}", bins.len());
+    /// ```
+    /// # Note
+    /// Edges are inclusive. A box with `west > east` crosses the antimeridian and covers the
+    /// longitudes from `west` to 180 and from -180 to `east`. Bins are returned in increasing
+    /// order.
+    pub fn bins_in_bbox(
+        &self,
+        north: f64,
+        south: f64,
+        west: f64,
+        east: f64,
+    ) -> Result<Vec<usize>, IsinError> {
+        self.require_one_based()?;
+        check_lat(north)?;
+        check_lat(south)?;
+        check_lon(west)?;
+        check_lon(east)?;
+
+        if south > north {
+            return Err(IsinError::InvalidParameter(format!(
+                "south {} is greater than north {}",
+                south, north
+            )));
+        }
+
+        let spans = if west <= east {
+            vec![(west, east)]
+        } else {
+            vec![(MIN_LON, east), (west, MAX_LON)]
+        };
+
+        let mut bins: Vec<usize> = Vec::new();
+
+        for row in self.row_of_lat(south)..=self.row_of_lat(north) {
+            if self.latbin[row] < south || self.latbin[row] > north {
+                continue;
+            }
+
+            // Center of column `col` is at (col + 0.5) * 360 / n - 180
+            let n = self.numbin[row] as f64;
+            for &(lo, hi) in spans.iter() {
+                let first = ((lo + 180.0) * n / 360.0 - 0.5).ceil().max(0.0) as usize;
+                let last = ((hi + 180.0) * n / 360.0 - 0.5).floor();
+                if last < 0.0 {
+                    continue;
+                }
+                let last = (last as usize).min(self.numbin[row] - 1);
+
+                bins.extend((first..=last).map(|col| self.basebin[row] + col));
+            }
+        }
+
+        Ok(bins)
+    }
+
+    /// Get the bins whose center is within a great-circle distance of a point
+    /// # Arguments
+    /// * `lon` - A longitude value
+    /// * `lat` - A latitude value
+    /// * `radius_km` - The search radius in kilometers
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let bins = isin.bins_within_radius(-68.5, 48.5, 10.0).unwrap();
+    /// println!("Bins: {:?}", bins.len());
+    /// ```
+    /// # Note
+    /// Candidates are taken from the rows of the latitude band covered by the radius and, within
+    /// each row, from the longitude extent of the spherical cap. They are then filtered with the
+    /// haversine distance on a sphere of radius 6371 km. When the cap contains a pole every bin
+    /// of a row is a candidate, and a radius of half the circumference or more returns every bin.
+    /// Bins are returned in increasing order.
+    pub fn bins_within_radius(
+        &self,
+        lon: f64,
+        lat: f64,
+        radius_km: f64,
+    ) -> Result<Vec<usize>, IsinError> {
+        self.require_one_based()?;
+        check_lon(lon)?;
+        check_lat(lat)?;
+
+        if !radius_km.is_finite() || radius_km < 0.0 {
+            return Err(IsinError::InvalidParameter(format!(
+                "radius must be a non-negative number of kilometers, got {}",
+                radius_km
+            )));
+        }
+
+        let angle = radius_km / EARTH_RADIUS_KM;
+        if angle >= std::f64::consts::PI {
+            return Ok((1..=self.totbin).collect());
+        }
+
+        let band = angle.to_degrees();
+        // Longitude half-width of the cap, unbounded when the cap contains a pole
+        let half_width = if angle + lat.to_radians().abs() < std::f64::consts::FRAC_PI_2 {
+            (angle.sin() / lat.to_radians().cos()).asin().to_degrees()
+        } else {
+            180.0
+        };
+
+        let mut bins: Vec<usize> = Vec::new();
+
+        for row in self.row_of_lat(clamp_lat(lat - band))..=self.row_of_lat(clamp_lat(lat + band)) {
+            let n = self.numbin[row];
+            let cols: Vec<usize> = if half_width >= 180.0 {
+                (0..n).collect()
+            } else {
+                let first = ((lon - half_width + 180.0) / 360.0 * n as f64).floor() as i64;
+                let last = ((lon + half_width + 180.0) / 360.0 * n as f64).floor() as i64;
+                (first..=last.min(first + n as i64 - 1))
+                    .map(|c| c.rem_euclid(n as i64) as usize)
+                    .collect()
+            };
+
+            for col in cols {
+                let center = (self.center_lon(row, col), self.latbin[row]);
+                if haversine_km((lon, lat), center, EARTH_RADIUS_KM) <= radius_km {
+                    bins.push(self.basebin[row] + col);
+                }
+            }
+        }
+
+        bins.sort_unstable();
+        Ok(bins)
+    }
+
+    /// Compute the great-circle distance between the centers of two bins
+    /// # Arguments
+    /// * `a` - A bin value
+    /// * `b` - A bin value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let km = isin.bin_distance_km(245535, 2963106).unwrap();
+    /// println!("Distance: {:?} km", km);
+    /// ```
+    /// # Note
+    /// The distance uses the haversine formula on a sphere of radius 6371 km. Use
+    /// `geo_math::haversine_km` for the distance between raw coordinates.
+    pub fn bin_distance_km(&self, a: usize, b: usize) -> Result<f64, IsinError> {
+        self.check_bin(a)?;
+        self.check_bin(b)?;
+
+        let (a, b) = (self.lonlat_of(a), self.lonlat_of(b));
+        Ok(haversine_km(a.into(), b.into(), EARTH_RADIUS_KM))
+    }
+
+    /// Get the bins sharing an edge with a bin
+    /// # Arguments
+    /// * `bin` - A bin value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// assert_eq!(isin.neighbors(1).unwrap(), vec![2, 3, 4, 5, 6]);
+    /// ```
+    /// # Note
+    /// The neighbors are the west and east bins of the same row, wrapping around the
+    /// antimeridian, and every bin of the adjacent rows whose longitude span overlaps the span of
+    /// the bin. Bins that only touch at a corner are not included, so this lies between the 4- and
+    /// 8-connected sets of `neighbor_degree`. Bins of the polar rows have no neighbor across the
+    /// pole, see `transpolar_neighbor`. Neighbors are returned in increasing order.
+    pub fn neighbors(&self, bin: usize) -> Result<Vec<usize>, IsinError> {
+        self.check_bin(bin)?;
+
+        let row = self.row_of_bin(bin);
+        let col = bin - self.basebin[row];
+        let n = self.numbin[row];
+
+        let mut bins = vec![
+            self.basebin[row] + (col + n - 1) % n,
+            self.basebin[row] + (col + 1) % n,
+        ];
+
+        for other in [row.wrapping_sub(1), row + 1] {
+            if other >= self.numrows {
+                continue;
+            }
+
+            // Columns j whose span [j/m, (j+1)/m] overlaps [col/n, (col+1)/n]
+            let m = self.numbin[other];
+            let first = col * m / n;
+            let last = ((col + 1) * m).div_ceil(n) - 1;
+            bins.extend((first..=last).map(|j| self.basebin[other] + j));
+        }
+
+        bins.retain(|&b| b != bin);
+        bins.sort_unstable();
+        bins.dedup();
+        Ok(bins)
+    }
+
+    /// Convert a bin to a WKT polygon
+    /// # Arguments
+    /// * `bin` - A bin value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// let wkt = isin.bin_to_wkt(1).unwrap();
+    /// assert!(wkt.starts_with("POLYGON((-180.00000000 -90.00000000, -60.00000000 -90.00000000"));
+    /// ```
+    /// # Note
+    /// The ring is `west south, east south, east north, west north, west south`, lon first, with 8
+    /// decimal places. Bins never straddle the 180 degree meridian because the columns of every
+    /// row start at -180, so the box is always valid without splitting.
+    pub fn bin_to_wkt(&self, bin: usize) -> Result<String, IsinError> {
+        self.check_bin(bin)?;
+        Ok(self.wkt_of(bin))
+    }
+
+    /// Convert bins to WKT polygons
+    /// # Arguments
+    /// * `bins` - A vector of bin values
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let wkt = isin.bins_to_wkt(&[245535, 245536]).unwrap();
+    /// println!("WKT: {:?}", wkt);
+    /// ```
+    pub fn bins_to_wkt(&self, bins: &[usize]) -> Result<Vec<String>, IsinError> {
+        self.check_bins(bins)?;
+        Ok(bins.iter().map(|&bin| self.wkt_of(bin)).collect())
+    }
+
+    /// Compute the area of a bin
+    /// # Arguments
+    /// * `bin` - A bin value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let km2 = isin.bin_area_km2(245535).unwrap();
+    /// println!("Area: {:?} km2", km2);
+    /// ```
+    /// # Note
+    /// The area of the spherical rectangle is `R^2 * (east - west) * (sin(north) - sin(south))`,
+    /// with R = 6371 km and the longitudes in radians. All the bins of a row have the same area,
+    /// see `row_area_km2`.
+    pub fn bin_area_km2(&self, bin: usize) -> Result<f64, IsinError> {
+        self.check_bin(bin)?;
+        Ok(self.area_of_row(self.row_of_bin(bin)))
+    }
+
+    /// Compute the area of each bin of a row
+    /// # Arguments
+    /// * `row` - A zero-based row, from south to north
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let km2 = isin.row_area_km2(2160).unwrap();
+    /// println!("Area: {:?} km2", km2);
+    /// ```
+    pub fn row_area_km2(&self, row: usize) -> Option<f64> {
+        (row < self.numrows).then(|| self.area_of_row(row))
+    }
+
+    /// Get the bin whose center is nearest to a point
+    /// # Arguments
+    /// * `lon` - A longitude value
+    /// * `lat` - A latitude value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// assert_eq!(isin.nearest_bin(-170.0, -80.5).unwrap(), 4);
+    /// assert_eq!(isin.lonlat_to_bin(-170.0, -80.5).unwrap(), 1);
+    /// ```
+    /// # Note
+    /// `lonlat2bin` returns the bin containing the point, while this returns the bin with the
+    /// nearest center in great-circle distance. They differ near the edges of wide bins, where
+    /// the center of a neighbor can be closer than the center of the containing bin. The
+    /// candidates are the containing bin and the bins sharing an edge with it, see `neighbors`.
+    pub fn nearest_bin(&self, lon: f64, lat: f64) -> Result<usize, IsinError> {
+        self.require_one_based()?;
+        check_lon(lon)?;
+        check_lat(lat)?;
+
+        let bin = self.bin_of(lon, lat);
+        let distance =
+            |b: usize| haversine_km((lon, lat), self.lonlat_of(b).into(), EARTH_RADIUS_KM);
+
+        let mut nearest = (bin, distance(bin));
+        for other in self.neighbors(bin)? {
+            let d = distance(other);
+            if d < nearest.1 {
+                nearest = (other, d);
+            }
+        }
+
+        Ok(nearest.0)
+    }
+
+    /// Get the range of bins of a row
+    /// # Arguments
+    /// * `row` - A zero-based row, from south to north
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// assert_eq!(isin.row_bin_range(1), Some(4..=12));
+    /// assert_eq!(isin.row_bin_range(18), None);
+    /// ```
+    /// # Note
+    /// The bins use the 1-based NASA numbering.
+    pub fn row_bin_range(&self, row: usize) -> Option<std::ops::RangeInclusive<usize>> {
+        (row < self.numrows).then(|| self.basebin[row]..=self.basebin[row] + self.numbin[row] - 1)
+    }
+
+    /// Rasterize bin values onto a regular global lon/lat grid
+    /// # Arguments
+    /// * `bins` - A vector of bin values
+    /// * `values` - The value of each bin
+    /// * `width` - The number of raster columns
+    /// * `height` - The number of raster rows
+    /// * `fill` - The value of the cells without data
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let raster = isin.rasterize(&[2963106], &[1.5], 360, 180, f64::NAN).unwrap();
+    /// assert_eq!(raster.len(), 360 * 180);
+    /// ```
+    /// # Note
+    /// The raster is row-major, starting at the north-west corner, and spans [-180, 180] by
+    /// [-90, 90]. Each cell takes the value of the bin containing its center, so a cell is
+    /// filled even when a finer grid of bins only partly covers it. When a bin is repeated the
+    /// last value wins.
+    pub fn rasterize(
+        &self,
+        bins: &[usize],
+        values: &[f64],
+        width: usize,
+        height: usize,
+        fill: f64,
+    ) -> Result<Vec<f64>, IsinError> {
+        self.rasterize_with_progress(bins, values, width, height, fill, None)
+    }
+
+    /// Rasterize bin values onto a regular global lon/lat grid, reporting progress along the way
+    /// # Arguments
+    /// * `bins` - A vector of bin values
+    /// * `values` - The value of each bin
+    /// * `width` - The number of raster columns
+    /// * `height` - The number of raster rows
+    /// * `fill` - The value of the cells without data
+    /// * `progress` - An optional callback receiving the number of cells done and the total
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let report = |done: usize, total: usize| println!("{}/{}", done, total);
+    /// let raster = isin
+    ///     .rasterize_with_progress(&[2963106], &[1.5], 360, 180, f64::NAN, Some(&report))
+    ///     .unwrap();
+    /// ```
+    /// # Note
+    /// The callback is invoked once per raster row, after the cells of that row are filled. See
+    /// `rasterize` for the layout of the raster.
+    pub fn rasterize_with_progress(
+        &self,
+        bins: &[usize],
+        values: &[f64],
+        width: usize,
+        height: usize,
+        fill: f64,
+        progress: Option<&dyn Fn(usize, usize)>,
+    ) -> Result<Vec<f64>, IsinError> {
+        check_lengths(bins.len(), values.len())?;
+        self.check_bins(bins)?;
+
+        if width == 0 || height == 0 {
+            return Err(IsinError::InvalidParameter(format!(
+                "raster dimensions must be positive, got {}x{}",
+                width, height
+            )));
+        }
+
+        let lookup: HashMap<usize, f64> =
+            bins.iter().copied().zip(values.iter().copied()).collect();
+        let mut raster: Vec<f64> = Vec::with_capacity(width * height);
+
+        for j in 0..height {
+            let lat = 90.0 - (j as f64 + 0.5) * 180.0 / height as f64;
+            for i in 0..width {
+                let lon = (i as f64 + 0.5) * 360.0 / width as f64 - 180.0;
+                raster.push(*lookup.get(&self.bin_of(lon, lat)).unwrap_or(&fill));
+            }
+
+            if let Some(progress) = progress {
+                progress(raster.len(), width * height);
+            }
+        }
+
+        Ok(raster)
+    }
+
+    /// Map bins to the bins of another grid
+    /// # Arguments
+    /// * `other` - The grid to map to
+    /// * `bins` - A vector of bin values of this grid
+    /// # Example
+    /// ```
+    /// let modis = l3bin::Isin::new(4320);
+    /// let seawifs = l3bin::Isin::new(2160);
+    /// let bins = modis.remap_bins(&seawifs, &[245535, 245536]).unwrap();
+    /// println!("Bins: {:?}", bins);
+    /// ```
+    /// # Note
+    /// Each bin is mapped to the bin of `other` containing its center. When `other` is coarser
+    /// the mapping is many-to-one, and when it is finer only the bin under the center is
+    /// returned, not all the bins covering the same area.
+    pub fn remap_bins(&self, other: &Isin, bins: &[usize]) -> Result<Vec<usize>, IsinError> {
+        self.check_bins(bins)?;
+        other.require_one_based()?;
+
+        Ok(bins
+            .iter()
+            .map(|&bin| {
+                let center = self.lonlat_of(bin);
+                other.bin_of(center.lon, center.lat)
+            })
+            .collect())
+    }
+
+    /// Bin point observations and compute per-bin statistics
+    /// # Arguments
+    /// * `lon` - A vector of longitude values
+    /// * `lat` - A vector of latitude values
+    /// * `values` - The observed value at each point
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let stats = isin.bin_stats(&[45.0, 45.001], &[45.0, 45.001], &[1.0, 3.0]).unwrap();
+    /// let bin = stats.bins()[0];
+    /// assert_eq!(stats.mean(bin), Some(2.0));
+    /// ```
+    /// # Note
+    /// Only the bins with observations are stored, so the statistics stay small for sparse data.
+    /// Bins use the 1-based NASA numbering.
+    pub fn bin_stats(
+        &self,
+        lon: &[f64],
+        lat: &[f64],
+        values: &[f64],
+    ) -> Result<BinStats, IsinError> {
+        self.require_one_based()?;
+        check_lengths(lon.len(), lat.len())?;
+        check_lengths(lon.len(), values.len())?;
+
+        let mut stats = BinStats::default();
+
+        for ((&lon, &lat), &value) in lon.iter().zip(lat.iter()).zip(values.iter()) {
+            check_lon(lon)?;
+            check_lat(lat)?;
+            stats.add(self.bin_of(lon, lat), value);
+        }
+
+        Ok(stats)
+    }
+
+    /// Get the k bins whose centers are nearest to a point
+    /// # Arguments
+    /// * `lon` - A longitude value
+    /// * `lat` - A latitude value
+    /// * `k` - The number of bins to return
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let nearest = isin.k_nearest_bins(-68.5, 48.5, 4).unwrap();
+    /// println!("Nearest: {:?}", nearest);
+    /// ```
+    /// # Note
+    /// The `(bin, distance_km)` pairs are sorted by increasing haversine distance on a sphere of
+    /// radius 6371 km. The search grows rings of edge-sharing neighbors from the containing bin
+    /// until it has k candidates and the newest ring holds nothing closer than the k-th one.
+    /// Fewer than k pairs are returned only when the grid has fewer than k bins.
+    pub fn k_nearest_bins(
+        &self,
+        lon: f64,
+        lat: f64,
+        k: usize,
+    ) -> Result<Vec<(usize, f64)>, IsinError> {
+        self.require_one_based()?;
+        check_lon(lon)?;
+        check_lat(lat)?;
+
+        if k == 0 {
+            return Ok(Vec::new());
+        }
+
+        let distance =
+            |b: usize| haversine_km((lon, lat), self.lonlat_of(b).into(), EARTH_RADIUS_KM);
+
+        let start = self.bin_of(lon, lat);
+        let mut visited: HashSet<usize> = HashSet::from([start]);
+        let mut ring: Vec<usize> = vec![start];
+        let mut found: Vec<(usize, f64)> = vec![(start, distance(start))];
+
+        loop {
+            let mut next_ring: Vec<usize> = Vec::new();
+            for &bin in ring.iter() {
+                for neighbor in self.neighbors(bin)? {
+                    if visited.insert(neighbor) {
+                        next_ring.push(neighbor);
+                    }
+                }
+            }
+
+            if next_ring.is_empty() {
+                break;
+            }
+
+            let closest = next_ring
+                .iter()
+                .map(|&b| {
+                    let d = distance(b);
+                    found.push((b, d));
+                    d
+                })
+                .fold(f64::INFINITY, f64::min);
+
+            if found.len() > k {
+                found.sort_by(|a, b| a.1.total_cmp(&b.1));
+                if closest > found[k - 1].1 {
+                    break;
+                }
+            }
+
+            ring = next_ring;
+        }
+
+        found.sort_by(|a, b| a.1.total_cmp(&b.1));
+        found.truncate(k);
+        Ok(found)
+    }
+
+    /// Check whether a point falls inside a bin
+    /// # Arguments
+    /// * `bin` - A bin value
+    /// * `lon` - A longitude value
+    /// * `lat` - A latitude value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// assert!(isin.bin_contains(1, -120.0, -85.0).unwrap());
+    /// assert!(!isin.bin_contains(1, 0.0, -85.0).unwrap());
+    /// ```
+    /// # Note
+    /// All four edges are inclusive, so a point on the edge between two bins is contained by
+    /// both, while `lonlat2bin` assigns it to one of them. The edges of a row are computed from
+    /// -180 and never extend past the antimeridian, so no longitude wrapping is needed.
+    pub fn bin_contains(&self, bin: usize, lon: f64, lat: f64) -> Result<bool, IsinError> {
+        self.check_bin(bin)?;
+        check_lon(lon)?;
+        check_lat(lat)?;
+
+        let b = self.bounds_of(bin);
+        Ok((b.south..=b.north).contains(&lat) && (b.west..=b.east).contains(&lon))
+    }
+
+    /// Get the hemispheres of a bin
+    /// # Arguments
+    /// * `bin` - A bin value
+    /// # Example
+    /// ```
+    /// use l3bin::{Hemisphere, Isin};
+    /// let isin = Isin::new(18);
+    /// assert_eq!(
+    ///     isin.bin_hemisphere(1).unwrap(),
+    ///     (Hemisphere::South, Hemisphere::West)
+    /// );
+    /// ```
+    /// # Note
+    /// The pair is (north-south, east-west) and is taken from the bin center. A bin straddling the
+    /// equator or the prime meridian is classified by the side its center is on, and only a
+    /// center exactly on the line gives `Equator` or `PrimeMeridian`. That happens for the middle
+    /// row of grids with an odd number of rows and the middle bin of rows with an odd number of
+    /// bins.
+    pub fn bin_hemisphere(&self, bin: usize) -> Result<(Hemisphere, Hemisphere), IsinError> {
+        self.check_bin(bin)?;
+
+        let center = self.lonlat_of(bin);
+        let north_south = match center.lat {
+            lat if lat > 0.0 => Hemisphere::North,
+            lat if lat < 0.0 => Hemisphere::South,
+            _ => Hemisphere::Equator,
+        };
+        let east_west = match center.lon {
+            lon if lon > 0.0 => Hemisphere::East,
+            lon if lon < 0.0 => Hemisphere::West,
+            _ => Hemisphere::PrimeMeridian,
+        };
+
+        Ok((north_south, east_west))
+    }
+
+    /// Get the bounds of a bin as boxes that do not cross the antimeridian
+    /// # Arguments
+    /// * `bin` - A bin value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// let boxes = isin.bin_to_split_bounds(12).unwrap();
+    /// assert_eq!(boxes, vec![(-70.0, -80.0, 140.0, 180.0)]);
+    /// ```
+    /// # Note
+    /// Boxes are `(north, south, west, east)`. Column edges are computed from -180, so the cells
+    /// of a row always start at -180 and end at 180 and no cell crosses the antimeridian: the
+    /// result is always a single box with longitudes in [-180, 180].
+    pub fn bin_to_split_bounds(&self, bin: usize) -> Result<Vec<(f64, f64, f64, f64)>, IsinError> {
+        self.check_bin(bin)?;
+
+        let bounds = self.bounds_of(bin);
+
+        Ok(vec![(bounds.north, bounds.south, bounds.west, bounds.east)])
+    }
+
+    /// Get the bins crossed by a great-circle path, in order
+    /// # Arguments
+    /// * `waypoints` - The (lon, lat) of the points of the path
+    /// * `step_km` - The spacing of the points sampled along the path, must be positive
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(4320);
+    /// let track = isin.bins_along_path(&[(-60.0, 40.0), (-50.0, 42.0)], 2.0).unwrap();
+    /// println!("Bins: {:?}", track);
+    /// ```
+    /// # Note
+    /// Each leg between consecutive waypoints is sampled at a spacing of at most `step_km`,
+    /// including both ends, and repeated consecutive bins are merged. A bin can appear again
+    /// later if the path comes back to it. Use a step smaller than the bin size so that no bin
+    /// is skipped. The path between antipodal waypoints is not defined.
+    pub fn bins_along_path(
+        &self,
+        waypoints: &[(f64, f64)],
+        step_km: f64,
+    ) -> Result<Vec<usize>, IsinError> {
+        self.require_one_based()?;
+
+        if !(step_km > 0.0 && step_km.is_finite()) {
+            return Err(IsinError::InvalidParameter(format!(
+                "step must be positive, got {}",
+                step_km
+            )));
+        }
+        for &(lon, lat) in waypoints {
+            check_lon(lon)?;
+            check_lat(lat)?;
+        }
+
+        let mut bins: Vec<usize> = Vec::new();
+        let mut push = |lon: f64, lat: f64| {
+            let bin = self.bin_of(lon, lat);
+            if bins.last() != Some(&bin) {
+                bins.push(bin);
+            }
+        };
+
+        if let Some(&(lon, lat)) = waypoints.first() {
+            push(lon, lat);
+        }
+        for leg in waypoints.windows(2) {
+            let distance = haversine_km(leg[0], leg[1], EARTH_RADIUS_KM);
+            let steps = (distance / step_km).ceil().max(1.0) as usize;
+
+            for i in 1..=steps {
+                let (lon, lat) = intermediate_point(leg[0], leg[1], i as f64 / steps as f64);
+                push(lon, lat);
+            }
+        }
+
+        Ok(bins)
+    }
+
+    /// Compress bins into ranges of consecutive bins within a row
+    /// # Arguments
+    /// * `bins` - A vector of bin values
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// let ranges = isin.compress_bins(&[2, 3, 4, 5, 9]).unwrap();
+    /// assert_eq!(ranges, vec![(2, 3), (4, 5), (9, 9)]);
+    /// ```
+    /// # Note
+    /// The bins are sorted and deduplicated first. Ranges are inclusive and never cross a row
+    /// boundary, so 3 and 4 above, the last bin of row 0 and the first of row 1, are in two ranges.
+    pub fn compress_bins(&self, bins: &[usize]) -> Result<Vec<(usize, usize)>, IsinError> {
+        self.check_bins(bins)?;
+
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+        for bin in sorted_set(bins) {
+            match ranges.last_mut() {
+                Some((start, end))
+                    if bin == *end + 1 && self.row_of_bin(bin) == self.row_of_bin(*start) =>
+                {
+                    *end = bin;
+                }
+                _ => ranges.push((bin, bin)),
+            }
+        }
+
+        Ok(ranges)
+    }
+
+    /// Expand ranges of bins, the inverse of `compress_bins`
+    /// # Arguments
+    /// * `ranges` - Inclusive `(start, end)` ranges of bins
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// assert_eq!(isin.decompress_bins(&[(2, 3), (4, 5)]).unwrap(), vec![2, 3, 4, 5]);
+    /// ```
+    /// # Note
+    /// Bins are returned in the order of the ranges. Ranges may cross rows, but `start` must not
+    /// be greater than `end`.
+    pub fn decompress_bins(&self, ranges: &[(usize, usize)]) -> Result<Vec<usize>, IsinError> {
+        let mut bins: Vec<usize> = Vec::new();
+
+        for &(start, end) in ranges {
+            self.check_bin(start)?;
+            self.check_bin(end)?;
+            if start > end {
+                return Err(IsinError::InvalidParameter(format!(
+                    "range {}..={} is empty",
+                    start, end
+                )));
+            }
+
+            bins.extend(start..=end);
+        }
+
+        Ok(bins)
+    }
+
+    /// Get every bin of the rows between two latitudes
+    /// # Arguments
+    /// * `south` - The southern latitude of the band
+    /// * `north` - The northern latitude of the band
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// assert_eq!(isin.bins_in_lat_band(-90.0, -85.0).unwrap(), vec![1, 2, 3]);
+    /// ```
+    /// # Note
+    /// Every row containing a latitude of the band is included in full, even if only its edge
+    /// is in the band; `bins_in_bbox` selects bins by their center instead. Bins are returned in
+    /// increasing order.
+    pub fn bins_in_lat_band(&self, south: f64, north: f64) -> Result<Vec<usize>, IsinError> {
+        self.require_one_based()?;
+        check_lat(south)?;
+        check_lat(north)?;
+
+        if south > north {
+            return Err(IsinError::InvalidParameter(format!(
+                "south {} is greater than north {}",
+                south, north
+            )));
+        }
+
+        let first = self.basebin[self.row_of_lat(south)];
+        let row = self.row_of_lat(north);
+        let last = self.basebin[row] + self.numbin[row] - 1;
+
+        Ok((first..=last).collect())
+    }
+
+    /// Get the bounding box enclosing the bounds of some bins
+    /// # Arguments
+    /// * `bins` - A vector of bin values, not empty
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// let bbox = isin.bins_bounding_box(&[1, 3]).unwrap();
+    /// assert_eq!(bbox, (-80.0, -90.0, 60.0, -60.0));
+    /// ```
+    /// # Note
+    /// The box is `(north, south, west, east)` and uses the shortest longitude span enclosing
+    /// every bin. When that span crosses the antimeridian `west > east`, as in `bins_in_bbox`:
+    /// above, bins 1 and 3 are closer across 180 than across 0.
+    pub fn bins_bounding_box(&self, bins: &[usize]) -> Result<(f64, f64, f64, f64), IsinError> {
+        self.check_bins(bins)?;
+        if bins.is_empty() {
+            return Err(IsinError::InvalidParameter(
+                "no bins to enclose".to_string(),
+            ));
+        }
+
+        let mut north = f64::MIN;
+        let mut south = f64::MAX;
+        let mut spans: Vec<(f64, f64)> = Vec::with_capacity(bins.len());
+
+        for &bin in bins {
+            let bounds = self.bounds_of(bin);
+            north = north.max(bounds.north);
+            south = south.min(bounds.south);
+            spans.push((bounds.west, bounds.east));
+        }
+
+        spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        // The box is the complement of the widest longitude gap between the bins, starting with
+        // the gap across the antimeridian.
+        let mut west = spans[0].0;
+        let mut east = spans[0].1;
+        let mut widest = spans[0].0 + 360.0 - spans.iter().map(|s| s.1).fold(f64::MIN, f64::max);
+        let mut reach = spans[0].1;
+
+        for span in &spans[1..] {
+            if span.0 - reach > widest {
+                widest = span.0 - reach;
+                west = span.0;
+                east = reach;
+            }
+            reach = reach.max(span.1);
+        }
+        if west <= east {
+            east = reach;
+        }
+
+        Ok((north, south, west, east))
+    }
+
+    /// Create a coarser grid with a fraction of the rows
+    /// # Arguments
+    /// * `factor` - The number of rows merged into one, must divide the number of rows
+    /// # Example
+    /// ```
+    /// let modis = l3bin::Isin::new(4320);
+    /// let coarse = modis.downsample_to(2).unwrap();
+    /// assert_eq!(coarse.num_rows(), 2160);
+    /// ```
+    /// # Note
+    /// The coarse grid must keep a number of rows divisible by 360, like the standard grids, and
+    /// keeps the bin numbering of this grid. Its bins are not unions of bins of this grid: map
+    /// fine bins to it with `map_bin_down`.
+    pub fn downsample_to(&self, factor: usize) -> Result<Isin, IsinError> {
+        if factor == 0 || !self.numrows.is_multiple_of(factor) {
+            return Err(IsinError::InvalidParameter(format!(
+                "factor {} does not divide {} rows",
+                factor, self.numrows
+            )));
+        }
+
+        let numrows = self.numrows / factor;
+        if !numrows.is_multiple_of(360) {
+            return Err(IsinError::InvalidParameter(format!(
+                "{} rows is not a multiple of 360",
+                numrows
+            )));
+        }
+
+        Ok(Isin::builder(numrows).bin_base(self.bin_base).build())
+    }
+
+    /// Map a bin to the bin of a coarser grid containing its center
+    /// # Arguments
+    /// * `coarse` - The grid to map to, usually from `downsample_to`
+    /// * `bin` - A bin value of this grid
+    /// # Example
+    /// ```
+    /// let modis = l3bin::Isin::new(4320);
+    /// let coarse = modis.downsample_to(2).unwrap();
+    /// let bin = modis.map_bin_down(&coarse, 245535).unwrap();
+    /// println!("Bin: {:?}", bin);
+    /// ```
+    /// # Note
+    /// This is `remap_bins` for a single bin.
+    pub fn map_bin_down(&self, coarse: &Isin, bin: usize) -> Result<usize, IsinError> {
+        self.check_bin(bin)?;
+        coarse.require_one_based()?;
+
+        let center = self.lonlat_of(bin);
+        Ok(coarse.bin_of(center.lon, center.lat))
+    }
+
+    /// Get the continuous row and column of a point
+    /// # Arguments
+    /// * `lon` - A longitude value
+    /// * `lat` - A latitude value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::Isin::new(18);
+    /// assert_eq!(isin.lonlat_to_fractional(-150.0, -85.0).unwrap(), (0.5, 0.25));
+    /// ```
+    /// # Note
+    /// The row is `(90 + lat) * numrows / 180` and the column `(lon + 180) * numbin / 360`, where
+    /// `numbin` is the number of bins of the row containing the point. Their integer parts are the
+    /// zero-based row and column of `bin_to_rowcol`, except on the north pole and at 180 where
+    /// they reach the number of rows and of bins of the row.
+    pub fn lonlat_to_fractional(&self, lon: f64, lat: f64) -> Result<(f64, f64), IsinError> {
+        check_lon(lon)?;
+        check_lat(lat)?;
+
+        let row = self.row_of_lat(lat);
+
+        Ok((
+            (90.0 + lat) * self.numrows as f64 / 180.0,
+            (lon + 180.0) * self.colscale[row],
+        ))
+    }
+
+    /// Interpolate bin values at a point
+    /// # Arguments
+    /// * `values` - The value of each bin with data
+    /// * `lon` - A longitude value
+    /// * `lat` - A latitude value
+    /// # Example
+    /// ```
+    /// use std::collections::HashMap;
+    /// let isin = l3bin::Isin::new(18);
+    /// let values: HashMap<usize, f64> = (1..=412).map(|b| (b, 2.0)).collect();
+    /// assert_eq!(isin.interpolate(&values, 10.0, 12.0).unwrap(), Some(2.0));
+    /// ```
+    /// # Note
+    /// The value is a bilinear blend of the two rows whose centers surround the point, and in
+    /// each row of the two bins whose centers surround its longitude, so rows with different
+    /// numbers of bins are matched by longitude. Columns wrap around the antimeridian, and
+    /// beyond the first and last row centers the nearest row is used. Bins without data are left
+    /// out and the weights of the others are rescaled; when they all have a zero weight their
+    /// plain mean is used. `None` is returned when none of the four bins has data.
+    pub fn interpolate(
+        &self,
+        values: &HashMap<usize, f64>,
+        lon: f64,
+        lat: f64,
+    ) -> Result<Option<f64>, IsinError> {
+        self.require_one_based()?;
+        check_lon(lon)?;
+        check_lat(lat)?;
+
+        let last_row = (self.numrows - 1) as f64;
+        let row_pos = ((90.0 + lat) * self.numrows as f64 / 180.0 - 0.5).clamp(0.0, last_row);
+        let south = row_pos.floor() as usize;
+        let north = (south + 1).min(self.numrows - 1);
+        let t = row_pos - south as f64;
+
+        let mut corners: Vec<(usize, f64)> = Vec::with_capacity(4);
+        for (row, row_weight) in [(south, 1.0 - t), (north, t)] {
+            let col_pos = (lon + 180.0) * self.colscale[row] - 0.5;
+            let west = col_pos.floor();
+            let s = col_pos - west;
+            let west = (west as i64).rem_euclid(self.numbin[row] as i64) as usize;
+            let east = (west + 1) % self.numbin[row];
+
+            corners.push((self.basebin[row] + west, row_weight * (1.0 - s)));
+            corners.push((self.basebin[row] + east, row_weight * s));
+        }
+
+        let with_data: Vec<(f64, f64)> = corners
+            .iter()
+            .filter_map(|&(bin, weight)| values.get(&bin).map(|&v| (v, weight)))
+            .collect();
+        if with_data.is_empty() {
+            return Ok(None);
+        }
+
+        let total: f64 = with_data.iter().map(|&(_, w)| w).sum();
+        if total > 0.0 {
+            Ok(Some(
+                with_data.iter().map(|&(v, w)| v * w).sum::<f64>() / total,
+            ))
+        } else {
+            let sum: f64 = with_data.iter().map(|&(v, _)| v).sum();
+            Ok(Some(sum / with_data.len() as f64))
+        }
+    }
+
+    fn check_bin(&self, bin: usize) -> Result<(), IsinError> {
+        self.require_one_based()?;
+
+        if bin < 1 || bin > self.totbin {
+            return Err(IsinError::InvalidBinRange {
+                bin,
+                min: 1,
+                max: self.totbin,
+            });
+        }
+
+        Ok(())
+    }
+
+    // Convert a bin in the numbering of the grid to the internal 1-based numbering
+    fn internal_bin(&self, bin: usize) -> Result<usize, IsinError> {
+        let shift = self.bin_shift();
+        match bin.checked_add(shift) {
+            Some(internal) if (1..=self.totbin).contains(&internal) => Ok(internal),
+            _ => Err(IsinError::InvalidBinRange {
+                bin,
+                min: 1 - shift,
+                max: self.totbin - shift,
+            }),
+        }
+    }
+
+    pub(crate) fn bin_shift(&self) -> usize {
+        match self.bin_base {
+            BinBase::One => 0,
+            BinBase::Zero => 1,
+        }
+    }
+
+    fn connected_bins(&self, bin: usize, connectivity: Connectivity) -> Vec<usize> {
+        if connectivity == Connectivity::Four {
+            return self.adjacent_bins(bin);
+        }
+
+        let row = self.row_of_bin(bin);
+        let col = bin - self.basebin[row];
+        let n = self.numbin[row];
+
+        let mut bins = vec![
+            self.basebin[row] + (col + n - 1) % n,
+            self.basebin[row] + (col + 1) % n,
+        ];
+
+        for other in [row.wrapping_sub(1), row + 1] {
+            if other >= self.numrows {
+                continue;
+            }
+
+            // Columns j whose span [j/m, (j+1)/m] touches [col/n, (col+1)/n]
+            let m = self.numbin[other];
+            let first = (col * m).div_ceil(n).saturating_sub(1);
+            let last = ((col + 1) * m / n).min(m - 1);
+            bins.extend((first..=last).map(|j| self.basebin[other] + j));
+
+            // Corners on the antimeridian touch the other end of the row
+            if col == 0 {
+                bins.push(self.basebin[other] + m - 1);
+            }
+            if col + 1 == n {
+                bins.push(self.basebin[other]);
+            }
+        }
+
+        bins.retain(|&b| b != bin);
+        bins.sort_unstable();
+        bins.dedup();
+        bins
+    }
+
+    fn check_record(&self, bin: usize, row: usize) -> Result<(), IsinError> {
+        self.check_bin(bin)?;
+
+        if bin > u32::MAX as usize || self.row_of_bin(bin) != row {
+            return Err(IsinError::InvalidParameter(format!(
+                "bin {} is not in row {}",
+                bin, row
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn check_bins(&self, bins: &[usize]) -> Result<(), IsinError> {
+        self.require_one_based()?;
+        bins.iter().try_for_each(|&bin| self.check_bin(bin))
+    }
+
+    // Methods other than the conversions use the 1-based NASA numbering, which would silently
+    // mix with the 0-based bins of a `BinBase::Zero` grid, so they refuse such grids.
+    fn require_one_based(&self) -> Result<(), IsinError> {
+        if self.bin_base != BinBase::One {
+            return Err(IsinError::InvalidParameter(
+                "only the conversions accept grids with BinBase::Zero, see IsinBuilder::bin_base"
+                    .to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn row_of_bin(&self, bin: usize) -> usize {
+        row_of_bin_in(&self.basebin, bin)
+    }
+
+    fn row_of_lat(&self, lat: f64) -> usize {
+        let row = ((90.0 + lat) * (self.numrows as f64) / 180.0) as usize;
+        row.min(self.numrows - 1)
+    }
+
+    pub(crate) fn bin_of(&self, lon: f64, lat: f64) -> usize {
+        let row = self.row_of_lat(lat);
+        self.basebin[row] + self.col_of_lon(row, lon)
+    }
+
+    fn col_of_lon(&self, row: usize, lon: f64) -> usize {
+        let col = ((lon + 180.0) * self.colscale[row]) as usize;
+        col.min(self.numbin[row] - 1)
+    }
+
+    // Column edges are computed from -180 so that the cells of a row never straddle the
+    // antimeridian, even with rounding.
+    pub(crate) fn bounds_of(&self, bin: usize) -> Bounds {
+        let row = self.row_of_bin(bin);
+        let col = bin - self.basebin[row];
+        let width = 360.0 / self.numbin[row] as f64;
+
+        Bounds {
+            north: self.latbin[row] + 90.0 / self.numrows as f64,
+            south: self.latbin[row] - 90.0 / self.numrows as f64,
+            west: col as f64 * width - 180.0,
+            east: (col + 1) as f64 * width - 180.0,
+        }
+    }
+
+    fn area_of_row(&self, row: usize) -> f64 {
+        let half_height = 90.0 / self.numrows as f64;
+        let north = (self.latbin[row] + half_height).to_radians();
+        let south = (self.latbin[row] - half_height).to_radians();
+        let width = (360.0 / self.numbin[row] as f64).to_radians();
+
+        EARTH_RADIUS_KM * EARTH_RADIUS_KM * width * (north.sin() - south.sin())
+    }
+
+    fn lonlat_of(&self, bin: usize) -> LonLat {
+        let row = self.row_of_bin(bin);
+
+        LonLat {
+            lon: self.center_lon(row, bin - self.basebin[row]),
+            lat: self.latbin[row],
+        }
+    }
+
+    fn wkt_of(&self, bin: usize) -> String {
+        let bounds = self.bounds_of(bin);
+
+        format!(
+            "POLYGON(({w:.8} {s:.8}, {e:.8} {s:.8}, {e:.8} {n:.8}, {w:.8} {n:.8}, {w:.8} {s:.8}))",
+            w = bounds.west,
+            e = bounds.east,
+            s = bounds.south,
+            n = bounds.north
+        )
+    }
+
+    fn center_lon(&self, row: usize, col: usize) -> f64 {
+        360.0 * (col as f64 + 0.5) / self.numbin[row] as f64 - 180.0
+    }
+
+    // West and east neighbors wrap around the antimeridian, north and south neighbors are the bins
+    // containing the center longitude in the adjacent rows.
+    fn adjacent_bins(&self, bin: usize) -> Vec<usize> {
+        let row = self.row_of_bin(bin);
+        let col = bin - self.basebin[row];
+        let n = self.numbin[row];
+        let lon = self.center_lon(row, col);
+
+        let mut bins = vec![
+            self.basebin[row] + (col + n - 1) % n,
+            self.basebin[row] + (col + 1) % n,
+        ];
+
+        if row + 1 < self.numrows {
+            bins.push(self.basebin[row + 1] + self.col_of_lon(row + 1, lon));
+        }
+
+        if row > 0 {
+            bins.push(self.basebin[row - 1] + self.col_of_lon(row - 1, lon));
+        }
+
+        bins.retain(|&b| b != bin);
+        bins.sort_unstable();
+        bins.dedup();
+        bins
+    }
+}
+
+/// Convert lonlat to bins in several grids at once
+/// # Arguments
+/// * `grids` - The grids to bin into, e.g. the levels of a pyramid
+/// * `lon` - A vector of longitude values
+/// * `lat` - A vector of latitude values
+/// # Example
+/// ```
+/// let grids = [l3bin::Isin::new(1080), l3bin::Isin::new(2160), l3bin::Isin::new(4320)];
+/// let bins = l3bin::bin_pyramid(&grids, &[45.0], &[45.0]).unwrap();
+/// println!("Bins: {:?}", bins);
+/// ```
+/// # Note
+/// The coordinates are validated once, and one vector of bins is returned per grid.
+pub fn bin_pyramid(grids: &[Isin], lon: &[f64], lat: &[f64]) -> Result<Vec<Vec<usize>>, IsinError> {
+    check_lengths(lat.len(), lon.len())?;
+    lon.iter().try_for_each(|&lon| check_lon(lon))?;
+    lat.iter().try_for_each(|&lat| check_lat(lat))?;
+
+    Ok(grids
+        .iter()
+        .map(|isin| {
+            lon.iter()
+                .zip(lat.iter())
+                .map(|(&lon, &lat)| isin.bin_of(lon, lat) - isin.bin_shift())
+                .collect()
+        })
+        .collect())
+}
+
+/// Convert a lonlat to a bin in several satellite grids at once
+/// # Arguments
+/// * `grids` - The grids to bin into, labeled by satellite
+/// * `lon` - A longitude value
+/// * `lat` - A latitude value
+/// # Example
+/// ```
+/// use l3bin::{Isin, Satellite};
+/// let modis = Isin::new(Satellite::Modis.num_latitude_rows());
+/// let seawifs = Isin::new(Satellite::Seawifs.num_latitude_rows());
+/// let grids = [(Satellite::Modis, &modis), (Satellite::Seawifs, &seawifs)];
+/// let bins = l3bin::lonlat2bin_multi(&grids, 45.0, 45.0).unwrap();
+/// println!("Bins: {:?}", bins);
+/// ```
+pub fn lonlat2bin_multi(
+    grids: &[(Satellite, &Isin)],
+    lon: f64,
+    lat: f64,
+) -> Result<Vec<(Satellite, usize)>, IsinError> {
+    check_lon(lon)?;
+    check_lat(lat)?;
+
+    Ok(grids
+        .iter()
+        .map(|&(sat, isin)| (sat, isin.bin_of(lon, lat) - isin.bin_shift()))
+        .collect())
+}
+
+/// Compute the Jaccard similarity between two bin coverages
+/// # Arguments
+/// * `a` - A vector of bin values
+/// * `b` - A vector of bin values
+/// # Example
+/// ```
+/// let similarity = l3bin::coverage_jaccard(&[1, 2, 3], &[2, 3, 4]);
+/// assert_eq!(similarity, 0.5);
+/// ```
+/// # Note
+/// The bins are compared as sets, so order and duplicates are ignored. Two empty coverages are
+/// identical and have a similarity of 1.
+pub fn coverage_jaccard(a: &[usize], b: &[usize]) -> f64 {
+    let a = sorted_set(a);
+    let b = sorted_set(b);
+
+    let (mut i, mut j, mut common) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                common += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+
+    let union = a.len() + b.len() - common;
+    if union == 0 {
+        return 1.0;
+    }
+
+    common as f64 / union as f64
+}
+
+/// Iterator over the `(bin, lon, lat)` centers of every bin of a grid, see `Isin::iter_centers`
+#[derive(Debug, Clone)]
+pub struct BinCenters<'a> {
+    isin: &'a Isin,
+    row: usize,
+    col: usize,
+    remaining: usize,
+}
+
+impl Iterator for BinCenters<'_> {
+    type Item = (usize, f64, f64);
+
+    fn next(&mut self) -> Option<(usize, f64, f64)> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let isin = self.isin;
+        let item = (
+            isin.basebin[self.row] + self.col - isin.bin_shift(),
+            isin.center_lon(self.row, self.col),
+            isin.latbin[self.row],
+        );
+
+        self.col += 1;
+        if self.col == isin.numbin[self.row] {
+            self.row += 1;
+            self.col = 0;
+        }
+        self.remaining -= 1;
+
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for BinCenters<'_> {}
+
+impl<'a> IntoIterator for &'a Isin {
+    type Item = (usize, f64, f64);
+    type IntoIter = BinCenters<'a>;
+
+    fn into_iter(self) -> BinCenters<'a> {
+        self.iter_centers()
+    }
+}
+
+struct Spiral<'a> {
+    isin: &'a Isin,
+    visited: HashSet<usize>,
+    ring: Vec<usize>,
+    pos: usize,
+    k: usize,
+    max_k: usize,
+}
+
+impl Iterator for Spiral<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.pos == self.ring.len() {
+            if self.k == self.max_k || self.ring.is_empty() {
+                return None;
+            }
+
+            let mut next_ring: Vec<usize> = Vec::new();
+
+            for &bin in self.ring.iter() {
+                for neighbor in self.isin.adjacent_bins(bin) {
+                    if self.visited.insert(neighbor) {
+                        next_ring.push(neighbor);
+                    }
+                }
+            }
+
+            next_ring.sort_unstable();
+            self.ring = next_ring;
+            self.pos = 0;
+            self.k += 1;
+        }
+
+        self.pos += 1;
+        Some(self.ring[self.pos - 1])
+    }
+}
+
+fn sorted_set(bins: &[usize]) -> Vec<usize> {
+    let mut bins = bins.to_vec();
+    bins.sort_unstable();
+    bins.dedup();
+    bins
+}
+
+pub(crate) fn row_center_lat(row: usize, numrows: usize) -> f64 {
+    ((row as f64 + 0.5) * 180.0 / (numrows as f64)) - 90.0
+}
+
+// First bin and number of bins of each row, and total number of bins of a grid. Shared with
+// `IsinF32` so that both grids get the same checks.
+pub(crate) fn row_layout(numrows: usize) -> Result<(Vec<usize>, Vec<usize>, usize), IsinError> {
+    if numrows == 0 {
+        return Err(IsinError::InvalidParameter(
+            "an ISIN grid needs at least one row".to_string(),
+        ));
+    }
+    if max_total_bins(numrows).is_none() {
+        return Err(IsinError::GridTooLarge { numrows });
+    }
+
+    let numbin: Vec<usize> = (0..numrows).map(|row| row_width(row, numrows)).collect();
+    let mut basebin: Vec<usize> = Vec::with_capacity(numrows);
+    basebin.push(1);
+    for row in 1..numrows {
+        basebin.push(basebin[row - 1] + numbin[row - 1]);
+    }
+
+    let totbin = basebin[numrows - 1] + numbin[numrows - 1] - 1;
+
+    Ok((basebin, numbin, totbin))
+}
+
+// Row of a 1-based bin, given the first bin of each row
+pub(crate) fn row_of_bin_in(basebin: &[usize], bin: usize) -> usize {
+    match basebin.binary_search(&bin) {
+        Ok(row) => row,
+        Err(row) => row - 1,
+    }
+}
+
+// Upper bound of the number of bins of a grid: no row has more than `2 * numrows + 1` bins, so
+// when this does not overflow neither do the `basebin` sums nor `totbin`.
+fn max_total_bins(numrows: usize) -> Option<usize> {
+    numrows.checked_mul(2)?.checked_add(1)?.checked_mul(numrows)
+}
+
+fn row_width(row: usize, numrows: usize) -> usize {
+    let lat = row_center_lat(row, numrows);
+    (2.0 * numrows as f64 * f64::cos(lat * std::f64::consts::PI / 180.0) + 0.5) as usize
+}
+
+fn check_lengths(expected: usize, found: usize) -> Result<(), IsinError> {
+    if expected != found {
+        return Err(IsinError::LengthMismatch { expected, found });
+    }
+
+    Ok(())
+}
+
+fn check_scale(scale: f64) -> Result<(), IsinError> {
+    if scale == 0.0 || !scale.is_finite() {
+        return Err(IsinError::InvalidParameter(format!(
+            "scale must be finite and non-zero, got {}",
+            scale
+        )));
+    }
+
+    Ok(())
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
@@ -0,0 +1,116 @@
+use crate::bounds_checker::{check_lat, check_lon};
+use crate::isin::{row_center_lat, row_layout, row_of_bin_in};
+use crate::IsinError;
+
+/// ISIN grid storing the row center latitudes as `f32`
+///
+/// The integer arrays are the same as in `Isin`, so bin numbers always match. Centers are
+/// returned as `f32`, which keeps about 7 significant digits: a center is within `1e-4` degrees
+/// of the `Isin` value, well below the size of a bin even on the MODIS grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IsinF32 {
+    basebin: Vec<usize>,
+    numbin: Vec<usize>,
+    latbin: Vec<f32>,
+    totbin: usize,
+    numrows: usize,
+}
+
+impl IsinF32 {
+    /// Create a new ISIN grid with `f32` centers
+    /// # Arguments
+    /// * `numrows` - The number of rows in the ISIN grid. MODIS is 4320, SeaWiFS is 2160.
+    /// # Example
+    /// ```
+    /// let isin = l3bin::IsinF32::new(4320);
+    /// assert_eq!(isin.total_bins(), 23761676);
+    /// ```
+    /// # Panics
+    /// Panics if `numrows` is zero or if the number of bins does not fit in a `usize`, see
+    /// `try_new`.
+    pub fn new(numrows: usize) -> IsinF32 {
+        IsinF32::try_new(numrows).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new ISIN grid with `f32` centers, failing instead of panicking
+    /// # Arguments
+    /// * `numrows` - The number of rows in the ISIN grid, at least 1
+    /// # Example
+    /// ```
+    /// assert!(l3bin::IsinF32::try_new(4320).is_ok());
+    /// assert!(l3bin::IsinF32::try_new(0).is_err());
+    /// ```
+    /// # Note
+    /// The rows are laid out and checked as in `Isin::try_new`.
+    pub fn try_new(numrows: usize) -> Result<IsinF32, IsinError> {
+        let (basebin, numbin, totbin) = row_layout(numrows)?;
+        let latbin: Vec<f32> = (0..numrows)
+            .map(|row| row_center_lat(row, numrows) as f32)
+            .collect();
+
+        Ok(IsinF32 {
+            basebin,
+            numbin,
+            latbin,
+            totbin,
+            numrows,
+        })
+    }
+
+    /// Get the total number of bins of the grid
+    pub fn total_bins(&self) -> usize {
+        self.totbin
+    }
+
+    /// Get the number of rows of the grid
+    pub fn num_rows(&self) -> usize {
+        self.numrows
+    }
+
+    /// Convert a single lonlat to a bin
+    /// # Arguments
+    /// * `lon` - A longitude value
+    /// * `lat` - A latitude value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::IsinF32::new(4320);
+    /// let bin = isin.lonlat_to_bin(45.0, 45.0).unwrap();
+    /// assert_eq!(bin, l3bin::Isin::new(4320).lonlat_to_bin(45.0, 45.0).unwrap());
+    /// ```
+    /// # Note
+    /// The bin is computed in `f64`, so it is the bin `Isin` gives for the same coordinates.
+    pub fn lonlat_to_bin(&self, lon: f32, lat: f32) -> Result<usize, IsinError> {
+        let (lon, lat) = (lon as f64, lat as f64);
+        check_lon(lon)?;
+        check_lat(lat)?;
+
+        let row = (((90.0 + lat) * self.numrows as f64 / 180.0) as usize).min(self.numrows - 1);
+        let col = ((lon + 180.0) * self.numbin[row] as f64 / 360.0) as usize;
+
+        Ok(self.basebin[row] + col.min(self.numbin[row] - 1))
+    }
+
+    /// Convert a single bin to the lonlat of its center
+    /// # Arguments
+    /// * `bin` - A bin value
+    /// # Example
+    /// ```
+    /// let isin = l3bin::IsinF32::new(18);
+    /// assert_eq!(isin.bin_to_lonlat(1).unwrap(), (-120.0, -85.0));
+    /// ```
+    pub fn bin_to_lonlat(&self, bin: usize) -> Result<(f32, f32), IsinError> {
+        if bin < 1 || bin > self.totbin {
+            return Err(IsinError::InvalidBinRange {
+                bin,
+                min: 1,
+                max: self.totbin,
+            });
+        }
+
+        let row = row_of_bin_in(&self.basebin, bin);
+        let col = bin - self.basebin[row];
+        let lon = 360.0 * (col as f64 + 0.5) / self.numbin[row] as f64 - 180.0;
+
+        Ok((lon as f32, self.latbin[row]))
+    }
+}
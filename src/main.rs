@@ -1,11 +1,147 @@
-use l3bin::Isin;
+use std::env;
+use std::io::{self, BufRead, Write};
+use std::process;
+
+use l3bin::{Isin, Satellite};
+
+const USAGE: &str = "\
+Usage: l3bin <COMMAND> [--sat <SATELLITE>] [ARGS]
+
+Commands:
+  lonlat2bin [--lon <LON> --lat <LAT>]   Convert a lonlat to a bin
+  bin2lonlat [--bin <BIN>]               Convert a bin to the lonlat of its center
+  bin2bounds [--bin <BIN>]               Convert a bin to its north,south,west,east bounds
+
+Options:
+  --sat <SATELLITE>   Grid to use, e.g. modis, seawifs or custom:2160 [default: modis]
+  -h, --help          Print this help
+
+Without coordinates or a bin, values are read from stdin, one per line (`lon,lat` or a bin),
+and one result is printed per line.";
+
+enum Command {
+    LonLat2Bin,
+    Bin2LonLat,
+    Bin2Bounds,
+}
+
+struct Args {
+    command: Command,
+    sat: Satellite,
+    lon: Option<f64>,
+    lat: Option<f64>,
+    bin: Option<usize>,
+}
 
 fn main() {
-    let isin = Isin::new(18);
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if args.is_empty() || args.iter().any(|a| a == "-h" || a == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+
+    if let Err(msg) = parse_args(&args).and_then(run) {
+        eprintln!("error: {}", msg);
+        process::exit(2);
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let command = match args[0].as_str() {
+        "lonlat2bin" => Command::LonLat2Bin,
+        "bin2lonlat" => Command::Bin2LonLat,
+        "bin2bounds" => Command::Bin2Bounds,
+        other => return Err(format!("unknown command {:?}, see --help", other)),
+    };
 
-    let res = isin.bin2bounds(&[367]);
+    let mut parsed = Args {
+        command,
+        sat: Satellite::Modis,
+        lon: None,
+        lat: None,
+        bin: None,
+    };
 
-    println!("{:?}", res);
+    let mut rest = args[1..].iter();
+    while let Some(flag) = rest.next() {
+        let value = rest
+            .next()
+            .ok_or_else(|| format!("missing value for {}", flag))?;
+
+        match flag.as_str() {
+            "--sat" => parsed.sat = value.parse().map_err(|e| format!("{}", e))?,
+            "--lon" => parsed.lon = Some(parse_value(value)?),
+            "--lat" => parsed.lat = Some(parse_value(value)?),
+            "--bin" => parsed.bin = Some(parse_value(value)?),
+            _ => return Err(format!("unknown option {:?}, see --help", flag)),
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let isin = Isin::try_new(args.sat.num_latitude_rows()).map_err(|e| e.to_string())?;
+
+    let single = match args.command {
+        Command::LonLat2Bin => match (args.lon, args.lat) {
+            (Some(lon), Some(lat)) => Some(format!("{},{}", lon, lat)),
+            (None, None) => None,
+            _ => return Err("--lon and --lat must be given together".to_string()),
+        },
+        _ => args.bin.map(|bin| bin.to_string()),
+    };
+
+    if let Some(line) = single {
+        println!("{}", convert(&isin, &args.command, &line)?);
+        return Ok(());
+    }
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for line in io::stdin().lock().lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let result = convert(&isin, &args.command, &line)?;
+        writeln!(out, "{}", result).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+fn convert(isin: &Isin, command: &Command, line: &str) -> Result<String, String> {
+    match command {
+        Command::LonLat2Bin => {
+            let (lon, lat) = line
+                .split_once(',')
+                .ok_or_else(|| format!("expected lon,lat, got {:?}", line))?;
+            let bin = isin
+                .lonlat_to_bin(parse_value(lon)?, parse_value(lat)?)
+                .map_err(|e| e.to_string())?;
+            Ok(bin.to_string())
+        }
+        Command::Bin2LonLat => {
+            let center = isin
+                .bin_to_lonlat(parse_value(line)?)
+                .map_err(|e| e.to_string())?;
+            Ok(format!("{},{}", center.lon, center.lat))
+        }
+        Command::Bin2Bounds => {
+            let b = isin
+                .bin_to_bounds(parse_value(line)?)
+                .map_err(|e| e.to_string())?;
+            Ok(format!("{},{},{},{}", b.north, b.south, b.west, b.east))
+        }
+    }
+}
 
-    println!("{:?}", isin.lonlat2bin(&[78.0], &[-36.0]));
+fn parse_value<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid value {:?}", value))
 }
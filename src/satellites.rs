@@ -0,0 +1,203 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::errors::{ParseSatelliteError, SatelliteError};
+use crate::geo_math::EARTH_RADIUS_KM;
+
+/// Sensors whose level 3 binned products use a standard ISIN grid
+///
+/// New variants may be added in minor releases, so matches outside of this crate need a wildcard
+/// arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Satellite {
+    /// MODIS Aqua and Terra, 4.6 km grid
+    Modis,
+    /// SeaWiFS, 9.2 km grid
+    Seawifs,
+    /// VIIRS, 4.6 km grid
+    Viirs,
+    /// MERIS, 4.6 km grid
+    Meris,
+    /// CZCS, 9.2 km grid
+    Czcs,
+    /// Sentinel-3 OLCI, 4.6 km grid
+    Sentinel3,
+    /// PACE OCI, 4.6 km grid
+    PaceOci,
+    /// SeaHawk HawkEye, 4.6 km grid
+    ///
+    /// HawkEye scenes are finer than this, the 4.6 km grid is the standard binned resolution.
+    /// Use `Custom` for finer grids.
+    HawkEye,
+    /// OCTS, 9.2 km grid
+    Octs,
+    /// A grid with a custom number of rows
+    Custom(usize),
+}
+
+impl Satellite {
+    /// Get the number of rows of the grid
+    /// # Example
+    /// ```
+    /// let rows = l3bin::Satellite::Modis.num_latitude_rows();
+    /// let isin = l3bin::Isin::new(rows);
+    /// ```
+    pub fn num_latitude_rows(&self) -> usize {
+        match self {
+            Satellite::Modis
+            | Satellite::Viirs
+            | Satellite::Meris
+            | Satellite::Sentinel3
+            | Satellite::PaceOci
+            | Satellite::HawkEye => 4320,
+            Satellite::Seawifs | Satellite::Czcs | Satellite::Octs => 2160,
+            Satellite::Custom(numrows) => *numrows,
+        }
+    }
+
+    /// Get every satellite with a standard grid
+    /// # Example
+    /// ```
+    /// for sat in l3bin::Satellite::all() {
+    ///     println!("{}: {} rows", sat, sat.num_latitude_rows());
+    /// }
+    /// ```
+    /// # Note
+    /// `Custom` is not included since it has no canonical number of rows.
+    pub fn all() -> &'static [Satellite] {
+        &[
+            Satellite::Modis,
+            Satellite::Seawifs,
+            Satellite::Viirs,
+            Satellite::Meris,
+            Satellite::Czcs,
+            Satellite::Sentinel3,
+            Satellite::PaceOci,
+            Satellite::HawkEye,
+            Satellite::Octs,
+        ]
+    }
+
+    /// Create a custom grid with a given number of rows
+    /// # Arguments
+    /// * `numrows` - The number of rows of the grid, at least 1
+    /// # Example
+    /// ```
+    /// let sat = l3bin::Satellite::with_num_latitude_rows(8640).unwrap();
+    /// let isin = l3bin::Isin::new(sat.num_latitude_rows());
+    /// ```
+    /// # Note
+    /// One row is the smallest grid that can be built, a single band of 2 bins; the grid math
+    /// needs nothing more. Standard grids have thousands of rows and a multiple of 360 rows, so
+    /// that row edges fall on every whole degree of latitude and on the equator.
+    pub fn with_num_latitude_rows(numrows: usize) -> Result<Satellite, SatelliteError> {
+        if numrows == 0 {
+            return Err(SatelliteError::ZeroRows);
+        }
+
+        Ok(Satellite::Custom(numrows))
+    }
+
+    /// Create a custom grid close to a given resolution
+    /// # Arguments
+    /// * `km` - The desired latitude spacing in km
+    /// # Example
+    /// ```
+    /// let sat = l3bin::Satellite::from_resolution_km(4.6).unwrap();
+    /// assert_eq!(sat.num_latitude_rows(), 4320);
+    /// ```
+    /// # Note
+    /// The number of rows is the multiple of 360 nearest to the resolution, and at least 360.
+    /// The grid is always `Custom`, compare `num_latitude_rows` to match a standard satellite.
+    pub fn from_resolution_km(km: f64) -> Result<Satellite, SatelliteError> {
+        if !(km > 0.0 && km.is_finite()) {
+            return Err(SatelliteError::InvalidResolution);
+        }
+
+        let numrows = 180.0 * km_per_degree() / km;
+        let multiple = ((numrows / 360.0).round() as usize).max(1);
+
+        Ok(Satellite::Custom(multiple * 360))
+    }
+
+    /// Get the latitude spacing of the grid in degrees
+    /// # Example
+    /// ```
+    /// assert_eq!(l3bin::Satellite::Seawifs.resolution_degrees(), 180.0 / 2160.0);
+    /// ```
+    pub fn resolution_degrees(&self) -> f64 {
+        180.0 / self.num_latitude_rows() as f64
+    }
+
+    /// Get the latitude spacing of the grid in km
+    /// # Example
+    /// ```
+    /// let km = l3bin::Satellite::Modis.nominal_resolution_km();
+    /// assert!((km - 4.63).abs() < 0.01);
+    /// ```
+    /// # Note
+    /// A degree of latitude is taken as about 111.2 km on a sphere of radius `EARTH_RADIUS_KM`.
+    /// Bins are about as wide as they are tall at every latitude.
+    pub fn nominal_resolution_km(&self) -> f64 {
+        self.resolution_degrees() * km_per_degree()
+    }
+}
+
+impl fmt::Display for Satellite {
+    /// Write the canonical lowercase name, `custom:<rows>` for custom grids
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Satellite::Modis => write!(f, "modis"),
+            Satellite::Seawifs => write!(f, "seawifs"),
+            Satellite::Viirs => write!(f, "viirs"),
+            Satellite::Meris => write!(f, "meris"),
+            Satellite::Czcs => write!(f, "czcs"),
+            Satellite::Sentinel3 => write!(f, "sentinel3"),
+            Satellite::PaceOci => write!(f, "pace-oci"),
+            Satellite::HawkEye => write!(f, "hawkeye"),
+            Satellite::Octs => write!(f, "octs"),
+            Satellite::Custom(numrows) => write!(f, "custom:{}", numrows),
+        }
+    }
+}
+
+impl FromStr for Satellite {
+    type Err = ParseSatelliteError;
+
+    /// Parse a satellite name, ignoring case
+    /// # Example
+    /// ```
+    /// use l3bin::Satellite;
+    /// assert_eq!("MODIS".parse::<Satellite>().unwrap(), Satellite::Modis);
+    /// assert_eq!("custom:2160".parse::<Satellite>().unwrap(), Satellite::Custom(2160));
+    /// ```
+    /// # Note
+    /// `sentinel-3` is accepted as well as `sentinel3`, and `paceoci`, `pace` and `oci` as well as
+    /// `pace-oci`.
+    /// Custom grids need at least one row, so `custom:0` is rejected.
+    fn from_str(s: &str) -> Result<Satellite, ParseSatelliteError> {
+        let name = s.trim().to_ascii_lowercase();
+
+        match name.as_str() {
+            "modis" => Ok(Satellite::Modis),
+            "seawifs" => Ok(Satellite::Seawifs),
+            "viirs" => Ok(Satellite::Viirs),
+            "meris" => Ok(Satellite::Meris),
+            "czcs" => Ok(Satellite::Czcs),
+            "sentinel3" | "sentinel-3" => Ok(Satellite::Sentinel3),
+            "pace-oci" | "paceoci" | "pace" | "oci" => Ok(Satellite::PaceOci),
+            "hawkeye" => Ok(Satellite::HawkEye),
+            "octs" => Ok(Satellite::Octs),
+            _ => name
+                .strip_prefix("custom:")
+                .and_then(|rows| rows.parse().ok())
+                .and_then(|rows| Satellite::with_num_latitude_rows(rows).ok())
+                .ok_or_else(|| ParseSatelliteError::new(s)),
+        }
+    }
+}
+
+fn km_per_degree() -> f64 {
+    EARTH_RADIUS_KM.to_radians()
+}
@@ -1,8 +1,9 @@
 // Implement tests for isin
 #[cfg(test)]
-
 mod tests {
-    use l3bin::Isin;
+    use l3bin::{
+        BinAccumulator, BinBase, CmpOp, Connectivity, Isin, IsinError, LatIndex, LonLat, Satellite,
+    };
 
     //check modis resturn 4320 rows
     // #[test]
@@ -20,51 +21,2139 @@ mod tests {
 
     // Check lonlat fails if lon is out of bounds
     #[test]
-    #[should_panic]
     fn test_lonlat2bin_lon_out_of_bounds() {
         let isin = Isin::new(4320);
         let lon = vec![181.0, 0.0];
         let lat = vec![0.0, 0.0];
-        isin.lonlat2bin(&lon, &lat);
+        assert_eq!(
+            isin.lonlat2bin(&lon, &lat),
+            Err(IsinError::InvalidLongitude {
+                lon: 181.0,
+                min: -180.0,
+                max: 180.0
+            })
+        );
     }
 
     // Check lonlat fails if lat is out of bounds
     #[test]
-    #[should_panic]
     fn test_lonlat2bin_lat_out_of_bounds() {
         let isin = Isin::new(4320);
         let lon = vec![0.0, 0.0];
         let lat = vec![91.0, 0.0];
-        isin.lonlat2bin(&lon, &lat);
+        assert_eq!(
+            isin.lonlat2bin(&lon, &lat),
+            Err(IsinError::InvalidLatitude {
+                lat: 91.0,
+                min: -90.0,
+                max: 90.0
+            })
+        );
+        assert!(matches!(
+            isin.lonlat2bin(&[0.0], &lat),
+            Err(IsinError::LengthMismatch { .. })
+        ));
     }
 
     // Check lat2row fails if lat is out of bounds
     #[test]
-    #[should_panic]
     fn test_lat2row_lat_out_of_bounds() {
         let isin = Isin::new(4320);
-        isin.lat2row(91.0);
+        assert!(matches!(
+            isin.lat2row(91.0),
+            Err(IsinError::InvalidLatitude { .. })
+        ));
     }
 
-    // Check bin2lonlat fails if bin is out of bounds
-    // #[test]
-    // #[should_panic]
-    // fn test_bin2lonlat_bin_out_of_bounds() {
-    //     let isin = Isin::new(4320);
-    //     let mut bin = vec![isin.totbin + 1];
-    //     isin.bin2lonlat(&mut bin);
-    // }
+    // Check bin2lonlat and bin2bounds fail if bin is out of bounds
+    #[test]
+    fn test_bin2lonlat_bin_out_of_bounds() {
+        let isin = Isin::new(4320);
+        let bin = vec![1, 23761677];
+        let err = IsinError::InvalidBinRange {
+            bin: 23761677,
+            min: 1,
+            max: 23761676,
+        };
+        assert_eq!(isin.bin2lonlat(&bin), Err(err.clone()));
+        assert_eq!(isin.bin2bounds(&bin), Err(err));
+        assert!(isin.bin2lonlat(&[0]).is_err());
+    }
 
-    // #[test]
-    // fn test_constrain_lat_lon() {
-    //     assert_eq!(Isin::constrain_lat(90.0), 90.0);
-    //     assert_eq!(Isin::constrain_lat(91.0), 90.0);
-    //     assert_eq!(Isin::constrain_lat(-91.0), -90.0);
-    //     assert_eq!(Isin::constrain_lat(-90.0), -90.0);
-    //
-    //     // assert_eq!(Isin::constrain_lon(180.0), 180.0);
-    //     assert_eq!(Isin::constrain_lon(181.0), 180.0);
-    //     assert_eq!(Isin::constrain_lon(-181.0), -180.0);
-    //     assert_eq!(Isin::constrain_lon(-180.0), -180.0);
-    // }
+    // Check latitudes are clamped and longitudes wrapped
+    #[test]
+    fn test_constrain_lat_lon() {
+        use l3bin::bounds_checker::{constrain_lat, constrain_lon};
+
+        assert_eq!(constrain_lat(90.0), 90.0);
+        assert_eq!(constrain_lat(91.0), 90.0);
+        assert_eq!(constrain_lat(-91.0), -90.0);
+        assert_eq!(constrain_lat(-90.0), -90.0);
+
+        assert_eq!(constrain_lon(180.0), 180.0);
+        assert_eq!(constrain_lon(181.0), -179.0);
+        assert_eq!(constrain_lon(-181.0), 179.0);
+        assert_eq!(constrain_lon(-180.0), -180.0);
+        assert_eq!(constrain_lon(725.0), 5.0);
+    }
+
+    // Check iter_spiral starts at the center and never repeats a bin
+    #[test]
+    fn test_iter_spiral() {
+        let isin = Isin::new(4320);
+        let bins: Vec<usize> = isin.iter_spiral(2963106, 3).unwrap().collect();
+        assert_eq!(bins[0], 2963106);
+
+        let mut unique = bins.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), bins.len());
+
+        assert_eq!(isin.iter_spiral(2963106, 0).unwrap().count(), 1);
+        assert_eq!(isin.iter_spiral(2963106, 1).unwrap().count(), 5);
+    }
+
+    // Check iter_spiral fails if the center bin is out of bounds
+    #[test]
+    fn test_iter_spiral_bin_out_of_bounds() {
+        let isin = Isin::new(4320);
+        assert!(isin.iter_spiral(0, 1).is_err());
+    }
+
+    // Check mirrored rows of the northern and southern hemispheres hold the same number of bins
+    #[test]
+    fn test_numbin_hemispheric_symmetry() {
+        for numrows in [18, 1080, 2160, 4320] {
+            let isin = Isin::new(numrows);
+            let numbin = |row: usize| {
+                let lat = (row as f64 + 0.5) * 180.0 / numrows as f64 - 90.0;
+                let bins = isin.lonlat2bin(&[-180.0, 180.0], &[lat, lat]).unwrap();
+                bins[1] - bins[0] + 1
+            };
+
+            for row in 0..numrows / 2 {
+                assert_eq!(numbin(row), numbin(numrows - 1 - row));
+            }
+        }
+    }
+
+    // Check transpolar_neighbor steps over the pole in the polar rows only
+    #[test]
+    fn test_transpolar_neighbor() {
+        let isin = Isin::new(18);
+
+        // The 3-bin polar rows map in a cycle, the mapping is not an involution
+        let expected = [(1, 3), (2, 1), (3, 2), (410, 412), (411, 410), (412, 411)];
+        for &(bin, other) in expected.iter() {
+            assert_eq!(isin.transpolar_neighbor(bin).unwrap(), Some(other));
+        }
+        assert_eq!(isin.transpolar_neighbor(100).unwrap(), None);
+        assert!(isin.transpolar_neighbor(0).is_err());
+    }
+
+    // Check classify_bins evaluates the mask on the bin centers
+    #[test]
+    fn test_classify_bins() {
+        let isin = Isin::new(4320);
+        let bins = isin.lonlat2bin(&[-45.0, 45.0], &[10.0, 10.0]).unwrap();
+        let ocean = isin.classify_bins(&bins, |lon, _lat| lon < 0.0).unwrap();
+        assert_eq!(ocean, vec![true, false]);
+
+        assert!(isin.classify_bins(&[0], |_, _| true).is_err());
+    }
+
+    // Check the adjacency is symmetric within a row and matches the first ring of iter_spiral
+    #[test]
+    fn test_build_adjacency_csr() {
+        let isin = Isin::new(18);
+        let csr = isin.build_adjacency_csr().unwrap();
+        assert_eq!(csr.indptr[0], 0);
+        assert_eq!(*csr.indptr.last().unwrap(), csr.indices.len());
+
+        let mut ring: Vec<usize> = isin.iter_spiral(200, 1).unwrap().skip(1).collect();
+        ring.sort_unstable();
+        assert_eq!(csr.neighbors(200), Some(ring.as_slice()));
+        assert!(csr.neighbors(201).unwrap().contains(&200));
+
+        // Invalid bins give None instead of panicking
+        assert_eq!(csr.neighbors(0), None);
+        assert_eq!(csr.neighbors(413), None);
+        assert_eq!(csr.neighbors(usize::MAX), None);
+
+        // A subset keeps only the neighbors inside of it
+        let subset = isin
+            .build_adjacency_csr_subset(&[201, 200, 200, 5])
+            .unwrap();
+        assert_eq!(subset.bins, Some(vec![5, 200, 201]));
+        assert_eq!(subset.neighbors(200), Some(&[201][..]));
+        assert_eq!(subset.neighbors(5), Some(&[][..]));
+        assert_eq!(subset.neighbors(199), None);
+        assert!(isin.build_adjacency_csr_subset(&[0]).is_err());
+    }
+
+    // Check the accumulator returns uniform and decay-weighted means
+    #[test]
+    fn test_bin_accumulator() {
+        let isin = Isin::new(18);
+        let mut acc = BinAccumulator::new(&isin);
+        acc.add(10, 1.0).unwrap();
+        acc.add(10, 3.0).unwrap();
+        acc.add_decayed(20, 1.0, 0.0, 5.0).unwrap();
+        acc.add_decayed(20, 4.0, 5.0, 5.0).unwrap();
+        assert!(acc.add(0, 1.0).is_err());
+        assert!(acc.add_decayed(20, 1.0, 1.0, 0.0).is_err());
+
+        let means = acc.finish();
+        assert_eq!(means[9], Some(2.0));
+        assert_eq!(means[19], Some(2.0));
+        assert_eq!(means[0], None);
+    }
+
+    // Check rowcol_extent spans the rows and columns of the bins
+    #[test]
+    fn test_rowcol_extent() {
+        let isin = Isin::new(18);
+        // Row 0 holds bins 1..=3 and row 1 starts at bin 4
+        assert_eq!(isin.rowcol_extent(&[2, 3, 5]).unwrap(), (0, 1, 1, 2));
+        assert!(isin.rowcol_extent(&[]).is_err());
+        assert!(isin.rowcol_extent(&[0]).is_err());
+    }
+
+    // Check the progress callback ends with all bins done
+    #[test]
+    fn test_build_adjacency_csr_progress() {
+        let isin = Isin::new(18);
+        let calls = std::cell::RefCell::new(Vec::new());
+        let report = |done: usize, total: usize| calls.borrow_mut().push((done, total));
+        let csr = isin
+            .build_adjacency_csr_with_progress(Some(&report))
+            .unwrap();
+
+        let calls = calls.into_inner();
+        assert_eq!(calls.len(), 18);
+        assert_eq!(calls[17].0, calls[17].1);
+        assert_eq!(csr, isin.build_adjacency_csr().unwrap());
+    }
+
+    // Check bins_fit_grid reports the offending extreme bin
+    #[test]
+    fn test_bins_fit_grid() {
+        let isin = Isin::new(18);
+        assert!(isin.bins_fit_grid(&[]).is_ok());
+        assert!(isin.bins_fit_grid(&[1, 2, 3]).is_ok());
+
+        match isin.bins_fit_grid(&[3, 100000, 2]) {
+            Err(l3bin::IsinError::InvalidBinRange { bin, .. }) => assert_eq!(bin, 100000),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match isin.bins_fit_grid(&[3, 0, 2]) {
+            Err(l3bin::IsinError::InvalidBinRange { bin, .. }) => assert_eq!(bin, 0),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    // Check the cosine weighting pulls the mean latitude towards the equator
+    #[test]
+    fn test_cosine_weighted_mean_lat() {
+        let isin = Isin::new(4320);
+        let bins = isin.lonlat2bin(&[0.0, 0.0], &[0.0, 60.0]).unwrap();
+        let lat = isin.cosine_weighted_mean_lat(&bins).unwrap();
+        assert!(lat > 0.0 && lat < 30.0);
+        assert!((lat - 20.0).abs() < 0.1);
+
+        assert!(isin.cosine_weighted_mean_lat(&[]).is_err());
+    }
+
+    // Check lonlat2bin_swath wraps unwrapped longitudes across the dateline
+    #[test]
+    fn test_lonlat2bin_swath() {
+        let isin = Isin::new(4320);
+        let bins = isin
+            .lonlat2bin_swath(&[179.9, 180.1, -180.1], &[10.0, 10.0, 10.0], true)
+            .unwrap();
+        let expected = isin
+            .lonlat2bin(&[179.9, -179.9, 179.9], &[10.0, 10.0, 10.0])
+            .unwrap();
+        assert_eq!(bins, expected);
+
+        assert!(isin.lonlat2bin_swath(&[180.1], &[10.0], false).is_err());
+        assert!(isin.lonlat2bin_swath(&[0.0], &[91.0], true).is_err());
+        assert!(isin.lonlat2bin_swath(&[0.0, 1.0], &[0.0], true).is_err());
+    }
+
+    // Check bin_to_uv and uv_to_bin round-trip
+    #[test]
+    fn test_bin_uv_round_trip() {
+        let isin = Isin::new(4320);
+        for bin in [1, 245535, 2963106, 23761676] {
+            let (u, v) = isin.bin_to_uv(bin).unwrap();
+            assert!((0.0..=1.0).contains(&u) && (0.0..=1.0).contains(&v));
+            assert_eq!(isin.uv_to_bin(u, v).unwrap(), bin);
+        }
+
+        assert_eq!(
+            isin.uv_to_bin(-1.0, 2.0).unwrap(),
+            isin.uv_to_bin(0.0, 1.0).unwrap()
+        );
+        assert!(isin.uv_to_bin(f64::NAN, 0.5).is_err());
+        assert!(isin.bin_to_uv(0).is_err());
+    }
+
+    // Check distance_to_bin_edge_km is largest at the bin center and vanishes on an edge
+    #[test]
+    fn test_distance_to_bin_edge_km() {
+        let isin = Isin::new(4320);
+        let center = isin.bin2lonlat(&[2963106]).unwrap()[0];
+        let (lon, lat) = center.into();
+        let bounds = isin.bin2bounds(&[2963106]).unwrap()[0];
+
+        let center = isin.distance_to_bin_edge_km(lon, lat).unwrap();
+        // Bins are about 4.6 km tall and wide, so the center is about 2.3 km from the edges
+        assert!(center > 2.0 && center < 2.5);
+
+        let edge = isin.distance_to_bin_edge_km(bounds.west, lat).unwrap();
+        assert!(edge < 1e-6);
+
+        assert!(isin.distance_to_bin_edge_km(-180.0, 0.0).unwrap() < 1e-6);
+        assert!(isin.distance_to_bin_edge_km(181.0, 0.0).is_err());
+    }
+
+    // Check bbox_mesh_bins returns the bins at the mesh nodes
+    #[test]
+    fn test_bbox_mesh_bins() {
+        let isin = Isin::new(4320);
+        let bins = isin.bbox_mesh_bins(-70.0, -60.0, 40.0, 50.0, 3, 2).unwrap();
+        let expected = isin
+            .lonlat2bin(
+                &[-70.0, -65.0, -60.0, -70.0, -65.0, -60.0],
+                &[50.0, 50.0, 50.0, 40.0, 40.0, 40.0],
+            )
+            .unwrap();
+        assert_eq!(bins, expected);
+
+        assert!(isin.bbox_mesh_bins(-70.0, -60.0, 50.0, 40.0, 3, 2).is_err());
+        assert!(isin.bbox_mesh_bins(-70.0, -60.0, 40.0, 50.0, 0, 2).is_err());
+    }
+
+    // Check bbox_mesh_bins steps across the antimeridian when west > east
+    #[test]
+    fn test_bbox_mesh_bins_antimeridian() {
+        let isin = Isin::new(4320);
+        let bins = isin.bbox_mesh_bins(170.0, -170.0, 0.0, 10.0, 5, 1).unwrap();
+        let expected = isin
+            .lonlat2bin(&[170.0, 175.0, 180.0, -175.0, -170.0], &[5.0; 5])
+            .unwrap();
+        assert_eq!(bins, expected);
+
+        let bins = isin.bbox_mesh_bins(170.0, -170.0, 0.0, 10.0, 1, 1).unwrap();
+        assert_eq!(bins, vec![isin.lonlat_to_bin(180.0, 5.0).unwrap()]);
+    }
+
+    // Check cf_coordinates holds one longitude per bin
+    #[test]
+    fn test_cf_coordinates() {
+        let isin = Isin::new(18);
+        let (lat, lon) = isin.cf_coordinates();
+        assert_eq!(lat.len(), 18);
+        assert_eq!(lon.len(), 18);
+
+        let totbin: usize = lon.iter().map(|row| row.len()).sum();
+        let last = isin.lonlat2bin(&[180.0], &[90.0 - 1e-9]).unwrap()[0];
+        assert_eq!(totbin, last);
+
+        assert_eq!(
+            isin.bin2lonlat(&[1]).unwrap()[0],
+            LonLat::new(lon[0][0], lat[0])
+        );
+    }
+
+    // Check the grids built for common resolutions satisfy the invariants
+    #[test]
+    fn test_check_invariants() {
+        for numrows in [1, 2, 18, 1080, 2160, 4320] {
+            assert!(Isin::new(numrows).check_invariants().is_ok());
+        }
+    }
+
+    // Check values survive packing to u16 within half a scale unit
+    #[test]
+    fn test_pack_values_u16() {
+        let isin = Isin::new(18);
+        // The 18 rows grid holds 412 bins
+        let mut values: Vec<Option<f64>> = (0..412).map(|i| Some(i as f64 * 0.037)).collect();
+        values[3] = None;
+        values[4] = Some(-5.0);
+
+        let packed = isin.pack_values_u16(&values, 0.01, 0.0, u16::MAX).unwrap();
+        assert_eq!(packed[3], u16::MAX);
+        assert_eq!(packed[4], 0);
+
+        let unpacked = isin
+            .unpack_values_u16(&packed, 0.01, 0.0, u16::MAX)
+            .unwrap();
+        assert_eq!(unpacked[3], None);
+        assert!((unpacked[10].unwrap() - 0.37).abs() <= 0.005);
+
+        assert!(isin.pack_values_u16(&values[1..], 0.01, 0.0, 0).is_err());
+        assert!(isin.pack_values_u16(&values, 0.0, 0.0, 0).is_err());
+    }
+
+    // Check bins_for_image_row returns a full grid row
+    #[test]
+    fn test_bins_for_image_row() {
+        let isin = Isin::new(18);
+        // The first image row of a 18 pixels high image matches the northernmost grid row
+        let bins = isin.bins_for_image_row(18, 0).unwrap();
+        assert_eq!(*bins.last().unwrap(), 412);
+        assert_eq!(bins.len(), 3);
+
+        let bins = isin.bins_for_image_row(36, 35).unwrap();
+        assert_eq!(bins, vec![1, 2, 3]);
+
+        assert!(isin.bins_for_image_row(18, 18).is_err());
+    }
+
+    // Check the great-circle helpers against the London - Paris reference
+    #[test]
+    fn test_geo_math() {
+        use l3bin::geo_math::{
+            destination_point, haversine_km, initial_bearing_deg, EARTH_RADIUS_KM,
+        };
+
+        let london = (-0.1278, 51.5074);
+        let paris = (2.3522, 48.8566);
+
+        let km = haversine_km(london, paris, EARTH_RADIUS_KM);
+        assert!((km - 343.5).abs() < 1.0);
+        assert!((initial_bearing_deg(london, paris) - 148.1).abs() < 0.1);
+
+        let (lon, lat) = destination_point(
+            london,
+            initial_bearing_deg(london, paris),
+            km,
+            EARTH_RADIUS_KM,
+        );
+        assert!((lon - paris.0).abs() < 1e-6 && (lat - paris.1).abs() < 1e-6);
+
+        let (lon, _) = destination_point((179.0, 0.0), 90.0, 2.0 * 111.19, EARTH_RADIUS_KM);
+        assert!((lon + 179.0).abs() < 1e-2);
+    }
+
+    // Check coverage_rle splits each row into runs covering the whole row
+    #[test]
+    fn test_coverage_rle() {
+        let isin = Isin::new(18);
+        let rle = isin.coverage_rle(&[2, 3, 5, 5]).unwrap();
+        assert_eq!(rle.len(), 18);
+        assert_eq!(rle[0], vec![(false, 1), (true, 2)]);
+        assert_eq!(rle[1], vec![(false, 1), (true, 1), (false, 7)]);
+        // The third row holds 15 bins
+        assert_eq!(rle[2], vec![(false, 15)]);
+
+        assert!(isin.coverage_rle(&[0]).is_err());
+    }
+
+    // Check bin_pyramid matches binning into each grid separately
+    #[test]
+    fn test_bin_pyramid() {
+        let grids = [Isin::new(1080), Isin::new(2160), Isin::new(4320)];
+        let lon = [45.0, -120.5, 179.9];
+        let lat = [45.0, -33.2, 0.1];
+
+        let bins = l3bin::bin_pyramid(&grids, &lon, &lat).unwrap();
+        assert_eq!(bins.len(), 3);
+        for (isin, level) in grids.iter().zip(bins.iter()) {
+            assert_eq!(*level, isin.lonlat2bin(&lon, &lat).unwrap());
+        }
+
+        assert!(l3bin::bin_pyramid(&grids, &[181.0], &[0.0]).is_err());
+    }
+
+    // Check to_geometry_columns emits closed polygons within the longitude range
+    #[test]
+    fn test_to_geometry_columns() {
+        let isin = Isin::new(18);
+        let (bins, wkt) = isin.to_geometry_columns(&[1, 3]).unwrap();
+        assert_eq!(bins, vec![1, 3]);
+        assert_eq!(
+            wkt[0],
+            "POLYGON((-180.00000000 -90.00000000, -60.00000000 -90.00000000, -60.00000000 -80.00000000, -180.00000000 -80.00000000, -180.00000000 -90.00000000))"
+        );
+        assert!(wkt[1].contains("180.00000000 -80.00000000"));
+        assert!(!wkt[1].contains("180.00000001"));
+
+        assert!(isin.to_geometry_columns(&[0]).is_err());
+    }
+
+    // Check threshold_bins applies each comparison and skips missing values
+    #[test]
+    fn test_threshold_bins() {
+        let isin = Isin::new(18);
+        let mut values: Vec<Option<f64>> = vec![Some(0.0); 412];
+        values[0] = Some(1.0);
+        values[1] = Some(2.0);
+        values[2] = None;
+
+        assert_eq!(
+            isin.threshold_bins(&values, CmpOp::Gt, 1.0).unwrap(),
+            vec![2]
+        );
+        assert_eq!(
+            isin.threshold_bins(&values, CmpOp::Ge, 1.0).unwrap(),
+            vec![1, 2]
+        );
+        assert_eq!(
+            isin.threshold_bins(&values, CmpOp::Lt, 0.0).unwrap(),
+            vec![]
+        );
+        assert_eq!(
+            isin.threshold_bins(&values, CmpOp::Le, 0.0).unwrap().len(),
+            409
+        );
+
+        assert!(isin.threshold_bins(&values[1..], CmpOp::Gt, 1.0).is_err());
+    }
+
+    // Check the longitude resolution is finest at the equator and coarsest at the poles
+    #[test]
+    fn test_row_lon_resolution_deg() {
+        let isin = Isin::new(18);
+        let resolution = isin.row_lon_resolution_deg();
+        assert_eq!(resolution.len(), 18);
+        assert_eq!(resolution[0], 120.0);
+        assert_eq!(resolution[17], 120.0);
+        assert!(resolution[9] < resolution[0]);
+        assert_eq!(resolution[9], 10.0);
+    }
+
+    // Check lonlat2bin_sorted_by_lat matches lonlat2bin and rejects unsorted latitudes
+    #[test]
+    fn test_lonlat2bin_sorted_by_lat() {
+        let isin = Isin::new(4320);
+        let lat: Vec<f64> = (0..1000).map(|i| -89.9 + i as f64 * 0.17).collect();
+        let lon: Vec<f64> = (0..1000).map(|i| -180.0 + i as f64 * 0.36).collect();
+
+        let bins = isin.lonlat2bin_sorted_by_lat(&lon, &lat).unwrap();
+        assert_eq!(bins, isin.lonlat2bin(&lon, &lat).unwrap());
+
+        match isin.lonlat2bin_sorted_by_lat(&[0.0, 0.0, 0.0], &[1.0, 2.0, 1.5]) {
+            Err(l3bin::IsinError::UnsortedLatitude { index }) => assert_eq!(index, 2),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    // Check bins_convex_hull keeps the corner bins and drops the interior ones
+    #[test]
+    fn test_bins_convex_hull() {
+        let isin = Isin::new(4320);
+        let mut lon = Vec::new();
+        let mut lat = Vec::new();
+        for i in 0..5 {
+            for j in 0..5 {
+                lon.push(178.0 + i as f64);
+                lat.push(10.0 + j as f64);
+            }
+        }
+        let lon: Vec<f64> = lon
+            .iter()
+            .map(|&l| if l > 180.0 { l - 360.0 } else { l })
+            .collect();
+        let bins = isin.lonlat2bin(&lon, &lat).unwrap();
+
+        let hull = isin.bins_convex_hull(&bins).unwrap();
+        assert!(hull.len() >= 4);
+        for &(hull_lon, hull_lat) in hull.iter() {
+            assert!(hull_lon.abs() > 177.0);
+            assert!((9.9..=14.1).contains(&hull_lat));
+        }
+
+        let center = isin.lonlat2bin(&[180.0], &[12.0]).unwrap()[0];
+        let center = isin.bin2lonlat(&[center]).unwrap()[0];
+        assert!(!hull.contains(&center.into()));
+
+        assert!(isin.bins_convex_hull(&[]).is_err());
+    }
+
+    // Check dissolve_by_value merges bins into an outer ring and a hole
+    #[test]
+    fn test_dissolve_by_value() {
+        let isin = Isin::new(18);
+        let mut values: Vec<Option<f64>> = vec![None; 412];
+
+        // Rows 8 to 10 span latitudes -10 to 20, with a single bin of another value in row 9
+        let first = isin.lonlat2bin(&[-180.0], &[-5.0]).unwrap()[0];
+        let last = isin.lonlat2bin(&[180.0], &[15.0]).unwrap()[0];
+        for bin in first..=last {
+            values[bin - 1] = Some(1.0);
+        }
+        let hole = isin.lonlat2bin(&[-125.0], &[5.0]).unwrap()[0];
+        values[hole - 1] = Some(2.0);
+
+        let polygons = isin.dissolve_by_value(&values).unwrap();
+        let signed_area = |ring: &[(f64, f64)]| {
+            ring.windows(2)
+                .map(|w| w[0].0 * w[1].1 - w[1].0 * w[0].1)
+                .sum::<f64>()
+                / 2.0
+        };
+
+        let ones: Vec<&Vec<(f64, f64)>> = polygons
+            .iter()
+            .filter(|(v, _)| *v == 1.0)
+            .map(|(_, ring)| ring)
+            .collect();
+        assert_eq!(ones.len(), 2);
+
+        let outer = ones.iter().find(|ring| signed_area(ring) > 0.0).unwrap();
+        assert_eq!(outer.len(), 5);
+        assert!((signed_area(outer) - 360.0 * 30.0).abs() < 1e-6);
+
+        let inner = ones.iter().find(|ring| signed_area(ring) < 0.0).unwrap();
+        assert!((signed_area(inner) + 10.0 * 10.0).abs() < 1e-6);
+
+        let twos: Vec<&(f64, Vec<(f64, f64)>)> =
+            polygons.iter().filter(|(v, _)| *v == 2.0).collect();
+        assert_eq!(twos.len(), 1);
+        assert_eq!(twos[0].1.len(), 5);
+
+        assert!(isin.dissolve_by_value(&values[1..]).is_err());
+    }
+
+    // Check lonlat2bin_multi labels the bin of each grid with its satellite
+    #[test]
+    fn test_lonlat2bin_multi() {
+        let modis = Isin::new(Satellite::Modis.num_latitude_rows());
+        let seawifs = Isin::new(Satellite::Seawifs.num_latitude_rows());
+        let grids = [(Satellite::Modis, &modis), (Satellite::Seawifs, &seawifs)];
+
+        let bins = l3bin::lonlat2bin_multi(&grids, -60.5, 42.1).unwrap();
+        assert_eq!(
+            bins,
+            vec![
+                (
+                    Satellite::Modis,
+                    modis.lonlat2bin(&[-60.5], &[42.1]).unwrap()[0]
+                ),
+                (
+                    Satellite::Seawifs,
+                    seawifs.lonlat2bin(&[-60.5], &[42.1]).unwrap()[0]
+                ),
+            ]
+        );
+
+        assert!(l3bin::lonlat2bin_multi(&grids, -60.5, 92.0).is_err());
+    }
+
+    // Check the row weights sum to one and favor the equator
+    #[test]
+    fn test_row_quadrature_weights() {
+        let isin = Isin::new(2160);
+        let weights = isin.row_quadrature_weights();
+        assert_eq!(weights.len(), 2160);
+        assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(weights[1080] > weights[0] * 100.0);
+        assert!((weights[10] - weights[2149]).abs() < 1e-15);
+    }
+
+    // Check lonlat2bin_checked flags coordinates given in radians
+    #[test]
+    fn test_lonlat2bin_checked() {
+        let isin = Isin::new(4320);
+        let lon = [-60.0, 45.0, 120.0];
+        let lat = [10.0, 45.0, -30.0];
+        assert_eq!(
+            isin.lonlat2bin_checked(&lon, &lat).unwrap(),
+            isin.lonlat2bin(&lon, &lat).unwrap()
+        );
+
+        let lon: Vec<f64> = lon.iter().map(|l: &f64| l.to_radians()).collect();
+        let lat: Vec<f64> = lat.iter().map(|l: &f64| l.to_radians()).collect();
+        assert_eq!(
+            isin.lonlat2bin_checked(&lon, &lat),
+            Err(l3bin::IsinError::SuspectedRadians)
+        );
+
+        // A small cluster near (0, 0) is not flagged
+        assert!(isin.lonlat2bin_checked(&[0.1, 0.2], &[0.1, 0.2]).is_ok());
+    }
+
+    // Check lonlat2bin and bin2lonlat round-trip under both bin bases
+    #[test]
+    fn test_bin_base_round_trip() {
+        let one = Isin::builder(4320).bin_base(BinBase::One).build();
+        let zero = Isin::builder(4320).bin_base(BinBase::Zero).build();
+        let lon = [-180.0, -60.5, 0.0, 45.0, 179.9];
+        let lat = [-90.0 + 1e-9, 42.1, 0.0, 45.0, 89.9];
+
+        let bins_one = one.lonlat2bin(&lon, &lat).unwrap();
+        let bins_zero = zero.lonlat2bin(&lon, &lat).unwrap();
+        assert_eq!(bins_zero[0], 0);
+        for (a, b) in bins_one.iter().zip(bins_zero.iter()) {
+            assert_eq!(*a, b + 1);
+        }
+
+        assert_eq!(
+            one.lonlat2bin(&lon, &lat).unwrap(),
+            Isin::new(4320).lonlat2bin(&lon, &lat).unwrap()
+        );
+        assert_eq!(
+            one.bin2lonlat(&bins_one).unwrap(),
+            zero.bin2lonlat(&bins_zero).unwrap()
+        );
+        assert_eq!(
+            one.bin2bounds(&bins_one).unwrap(),
+            zero.bin2bounds(&bins_zero).unwrap()
+        );
+
+        let centers = zero.bin2lonlat(&bins_zero).unwrap();
+        let lon: Vec<f64> = centers.iter().map(|c| c.lon).collect();
+        let lat: Vec<f64> = centers.iter().map(|c| c.lat).collect();
+        assert_eq!(zero.lonlat2bin(&lon, &lat).unwrap(), bins_zero);
+
+        // The largest bin is rejected instead of overflowing when shifted
+        assert!(matches!(
+            zero.bin_to_lonlat(usize::MAX),
+            Err(IsinError::InvalidBinRange { .. })
+        ));
+        assert!(zero.bin2lonlat(&[usize::MAX]).is_err());
+        assert!(zero.row_for_bin(usize::MAX).is_err());
+    }
+
+    // Check neighbor degrees are full at the antimeridian and reduced at the poles
+    #[test]
+    fn test_neighbor_degree() {
+        let isin = Isin::new(4320);
+        let mid = isin.lonlat2bin(&[10.0], &[45.0]).unwrap()[0];
+        assert_eq!(isin.neighbor_degree(mid, Connectivity::Four).unwrap(), 4);
+        assert!(isin.neighbor_degree(mid, Connectivity::Eight).unwrap() >= 6);
+
+        let dateline = isin.lonlat2bin(&[-180.0], &[45.0]).unwrap()[0];
+        assert_eq!(
+            isin.neighbor_degree(dateline, Connectivity::Four).unwrap(),
+            4
+        );
+        assert!(isin.neighbor_degree(dateline, Connectivity::Eight).unwrap() >= 6);
+
+        assert_eq!(isin.neighbor_degree(1, Connectivity::Four).unwrap(), 3);
+        assert_eq!(
+            isin.neighbor_degrees(&[mid, 1], Connectivity::Four)
+                .unwrap(),
+            vec![4, 3]
+        );
+        assert!(isin.neighbor_degree(0, Connectivity::Four).is_err());
+    }
+
+    // Check bin records round-trip and reject a row that does not hold the bin
+    #[test]
+    fn test_bin_record() {
+        let isin = Isin::new(4320);
+        let bin = isin.lonlat2bin(&[12.0], &[-33.0]).unwrap()[0];
+        let row = isin.lat2row(-33.0).unwrap() - 1;
+
+        let packed = isin.pack_bin_record(bin, row).unwrap();
+        assert_eq!(packed >> 32, row as u64);
+        assert_eq!(isin.unpack_bin_record(packed).unwrap(), (bin, row));
+
+        assert!(isin.pack_bin_record(bin, row + 1).is_err());
+        assert!(isin.unpack_bin_record(packed + (1 << 32)).is_err());
+        assert!(isin.unpack_bin_record(0).is_err());
+    }
+
+    // Check the day side follows the subsolar point at the June solstice
+    #[test]
+    fn test_sunlit_bins() {
+        let isin = Isin::new(2160);
+        // 2024-06-21 12:00:00 UTC, the sun is overhead near (0, 23.4)
+        let t = 1718971200;
+        let noon = isin.lonlat2bin(&[0.0], &[23.0]).unwrap()[0];
+        let midnight = isin.lonlat2bin(&[180.0], &[0.0]).unwrap()[0];
+        let north_pole = isin.lonlat2bin(&[0.0], &[89.9]).unwrap()[0];
+        let south_pole = isin.lonlat2bin(&[0.0], &[-89.9]).unwrap()[0];
+
+        let day = isin
+            .sunlit_bins(&[noon, midnight, north_pole, south_pole], t)
+            .unwrap();
+        assert_eq!(day, vec![noon, north_pole]);
+
+        // Twelve hours later the night side faces the sun
+        let day = isin.sunlit_bins(&[noon, midnight], t + 43200).unwrap();
+        assert_eq!(day, vec![midnight]);
+
+        assert!(isin.sunlit_bins(&[0], t).is_err());
+    }
+
+    // Check the bins crossed by a parallel and a meridian
+    #[test]
+    fn test_bins_on_parallel_and_meridian() {
+        let isin = Isin::new(4320);
+
+        let parallel = isin.bins_on_parallel(45.0).unwrap();
+        assert!(parallel.len() < isin.bins_on_parallel(0.0).unwrap().len());
+        assert!(parallel.windows(2).all(|w| w[1] == w[0] + 1));
+        assert!(parallel.contains(&isin.lonlat2bin(&[12.3], &[45.0]).unwrap()[0]));
+
+        let meridian = isin.bins_on_meridian(-63.5).unwrap();
+        assert_eq!(meridian.len(), 4320);
+        assert!(meridian.contains(&isin.lonlat2bin(&[-63.5], &[45.0]).unwrap()[0]));
+
+        assert!(isin.bins_on_parallel(91.0).is_err());
+        assert!(isin.bins_on_meridian(-181.0).is_err());
+    }
+
+    // Check the Jaccard similarity between bin coverages
+    #[test]
+    fn test_coverage_jaccard() {
+        assert_eq!(l3bin::coverage_jaccard(&[3, 1, 2, 2], &[4, 2, 3]), 0.5);
+        assert_eq!(l3bin::coverage_jaccard(&[1, 2], &[2, 1, 1]), 1.0);
+        assert_eq!(l3bin::coverage_jaccard(&[1, 2], &[3, 4]), 0.0);
+        assert_eq!(l3bin::coverage_jaccard(&[1], &[]), 0.0);
+        assert_eq!(l3bin::coverage_jaccard(&[], &[]), 1.0);
+    }
+
+    // Check the length of the edge shared by two bins
+    #[test]
+    fn test_shared_edge_length_km() {
+        let isin = Isin::new(18);
+        let row_height = 6371.0 * 10f64.to_radians();
+
+        // Same row, including across the antimeridian
+        assert!((isin.shared_edge_length_km(5, 6).unwrap() - row_height).abs() < 1e-9);
+        assert!((isin.shared_edge_length_km(4, 12).unwrap() - row_height).abs() < 1e-9);
+        assert_eq!(isin.shared_edge_length_km(5, 7).unwrap(), 0.0);
+        assert_eq!(isin.shared_edge_length_km(5, 5).unwrap(), 0.0);
+
+        // Bin 1 spans 120 degrees along the -80 parallel and bin 4 spans 40 degrees
+        let km = 6371.0 * 80f64.to_radians().cos() * 40f64.to_radians();
+        assert!((isin.shared_edge_length_km(1, 4).unwrap() - km).abs() < 1e-9);
+        assert!((isin.shared_edge_length_km(4, 1).unwrap() - km).abs() < 1e-9);
+        assert_eq!(isin.shared_edge_length_km(1, 7).unwrap(), 0.0);
+        assert_eq!(isin.shared_edge_length_km(1, 13).unwrap(), 0.0);
+
+        assert!(isin.shared_edge_length_km(0, 1).is_err());
+    }
+
+    // Check the CSV reader bins and averages rows and counts the skipped ones
+    #[cfg(feature = "csv")]
+    #[test]
+    fn test_accumulate_csv() {
+        let isin = Isin::new(4320);
+        let data = "lon,lat,value\n\
+                    -63.5,45.0,1.0\n\
+                    -63.5,45.0,2.0\n\
+                    \n\
+                    120.0,-30.0,5.0\n\
+                    200.0,0.0,1.0\n\
+                    1.0,2.0\n\
+                    1.0,2.0,nan\n\
+                    a,b,c\n";
+
+        let (means, skipped) = l3bin::csv::accumulate_csv(&isin, data.as_bytes()).unwrap();
+        let bins = isin.lonlat2bin(&[-63.5, 120.0], &[45.0, -30.0]).unwrap();
+
+        assert_eq!(means.len(), 23761676);
+        assert_eq!(means[bins[0] - 1], Some(1.5));
+        assert_eq!(means[bins[1] - 1], Some(5.0));
+        assert_eq!(means.iter().filter(|m| m.is_some()).count(), 2);
+        assert_eq!(skipped, 4);
+    }
+
+    // Check bin2bounds returns named edges matching the grid geometry
+    #[test]
+    fn test_bin2bounds() {
+        let isin = Isin::new(18);
+        let bounds = isin.bin2bounds(&[1, 4, 412]).unwrap();
+
+        assert_eq!(
+            bounds[0],
+            l3bin::Bounds {
+                north: -80.0,
+                south: -90.0,
+                west: -180.0,
+                east: -60.0,
+            }
+        );
+        assert!((bounds[1].width() - 40.0).abs() < 1e-9);
+        assert!((bounds[1].height() - 10.0).abs() < 1e-9);
+        assert_eq!(bounds[2].north, 90.0);
+        assert!((bounds[2].east - 180.0).abs() < 1e-9);
+    }
+
+    // Check bin2lonlat returns the bin centers
+    #[test]
+    fn test_bin2lonlat() {
+        let isin = Isin::new(18);
+        let centers = isin.bin2lonlat(&[1, 4, 412]).unwrap();
+
+        assert_eq!(centers[0], LonLat::new(-120.0, -85.0));
+        assert!((centers[1].lon + 160.0).abs() < 1e-9);
+        assert_eq!(centers[1].lat, -75.0);
+        assert!((centers[2].lon - 120.0).abs() < 1e-9);
+        assert_eq!(centers[2].lat, 85.0);
+
+        let (lon, lat): (f64, f64) = centers[0].into();
+        assert_eq!((lon, lat), (-120.0, -85.0));
+    }
+
+    // Check the single-bin conversions agree with the slice methods
+    #[test]
+    fn test_scalar_conversions() {
+        let isin = Isin::new(4320);
+        let bins = [1, 245535, 2963106, 23761676];
+
+        for (&bin, center) in bins.iter().zip(isin.bin2lonlat(&bins).unwrap()) {
+            assert_eq!(isin.bin_to_lonlat(bin).unwrap(), center);
+            assert_eq!(isin.lonlat_to_bin(center.lon, center.lat).unwrap(), bin);
+        }
+        for (&bin, bounds) in bins.iter().zip(isin.bin2bounds(&bins).unwrap()) {
+            assert_eq!(isin.bin_to_bounds(bin).unwrap(), bounds);
+        }
+
+        assert!(isin.bin_to_lonlat(0).is_err());
+        assert!(isin.bin_to_bounds(23761677).is_err());
+        assert!(isin.lonlat_to_bin(0.0, 91.0).is_err());
+
+        let zero = Isin::builder(4320).bin_base(BinBase::Zero).build();
+        assert_eq!(zero.lonlat_to_bin(-180.0, -90.0).unwrap(), 0);
+        assert!(zero.bin_to_lonlat(0).is_ok());
+        assert!(zero.bin_to_lonlat(23761676).is_err());
+    }
+
+    // Check the poles map into the polar rows instead of panicking
+    #[test]
+    fn test_lonlat2bin_at_poles() {
+        let isin = Isin::new(Satellite::Modis.num_latitude_rows());
+
+        assert_eq!(isin.lat2row(90.0).unwrap(), 4320);
+        assert_eq!(isin.lat2row(-90.0).unwrap(), 1);
+
+        let bins = isin.lonlat2bin(&[0.0, 0.0], &[90.0, -90.0]).unwrap();
+        assert_eq!(bins[0], isin.lonlat2bin(&[0.0], &[89.99]).unwrap()[0]);
+        assert_eq!(bins[1], isin.lonlat2bin(&[0.0], &[-89.99]).unwrap()[0]);
+
+        let centers = isin.bin2lonlat(&bins).unwrap();
+        assert!(centers[0].lat > 89.97);
+        assert!(centers[1].lat < -89.97);
+    }
+
+    // Check lonlat2bin_clamped bins noisy coordinates at the grid edges
+    #[test]
+    fn test_lonlat2bin_clamped() {
+        let isin = Isin::new(4320);
+        let lon = [180.0000001, -181.0, 45.0, 0.0];
+        let lat = [0.0, 90.0000001, -95.0, 45.0];
+
+        assert_eq!(
+            isin.lonlat2bin_clamped(&lon, &lat),
+            isin.lonlat2bin(&[180.0, -180.0, 45.0, 0.0], &[0.0, 90.0, -90.0, 45.0])
+                .unwrap()
+        );
+        assert!(isin.lonlat2bin(&lon, &lat).is_err());
+    }
+
+    // Check the grid accessors agree with each other
+    #[test]
+    fn test_grid_accessors() {
+        let isin = Isin::new(Satellite::Seawifs.num_latitude_rows());
+        assert_eq!(isin.num_rows(), 2160);
+        assert_eq!(isin.total_bins(), 5940422);
+
+        let sum: usize = (0..isin.num_rows())
+            .map(|row| isin.bins_in_row(row).unwrap())
+            .sum();
+        assert_eq!(sum, isin.total_bins());
+
+        let last = isin.num_rows() - 1;
+        assert_eq!(
+            isin.base_bin(last).unwrap() + isin.bins_in_row(last).unwrap() - 1,
+            isin.total_bins()
+        );
+        assert!(isin.row_latitude(last).unwrap() > 89.9);
+
+        assert_eq!(isin.bins_in_row(2160), None);
+        assert_eq!(isin.base_bin(2160), None);
+        assert_eq!(isin.row_latitude(2160), None);
+    }
+
+    // Check bin_to_rowcol and rowcol_to_bin round-trip
+    #[test]
+    fn test_bin_rowcol() {
+        let isin = Isin::new(4320);
+        for &bin in [1, 245535, 2963106, 23761676].iter() {
+            let (row, col) = isin.bin_to_rowcol(bin).unwrap();
+            assert_eq!(isin.rowcol_to_bin(row, col).unwrap(), bin);
+        }
+        assert_eq!(isin.bin_to_rowcol(245535).unwrap().0, 280);
+        assert_eq!(isin.bin_to_rowcol(23761676).unwrap(), (4319, 2));
+
+        assert!(isin.bin_to_rowcol(0).is_err());
+        assert!(isin.rowcol_to_bin(4320, 0).is_err());
+        assert!(isin.rowcol_to_bin(0, 3).is_err());
+
+        let zero = Isin::builder(4320).bin_base(BinBase::Zero).build();
+        assert_eq!(zero.bin_to_rowcol(0).unwrap(), (0, 0));
+        assert_eq!(zero.rowcol_to_bin(0, 0).unwrap(), 0);
+    }
+
+    // Check iter_centers walks every bin in order and matches bin2lonlat
+    #[test]
+    fn test_iter_centers() {
+        let isin = Isin::new(18);
+        let centers: Vec<(usize, f64, f64)> = isin.iter_centers().collect();
+        assert_eq!(centers.len(), 412);
+
+        let bins: Vec<usize> = centers.iter().map(|c| c.0).collect();
+        assert_eq!(bins, (1..=412).collect::<Vec<usize>>());
+
+        for (c, expected) in centers.iter().zip(isin.bin2lonlat(&bins).unwrap()) {
+            assert_eq!(LonLat::new(c.1, c.2), expected);
+        }
+
+        let zero = Isin::builder(18).bin_base(BinBase::Zero).build();
+        assert_eq!(zero.iter_centers().next().unwrap().0, 0);
+    }
+
+    // Check bins_in_bbox keeps exactly the bins whose center is inside the box
+    #[test]
+    fn test_bins_in_bbox() {
+        let isin = Isin::new(180);
+        let inside = |c: &LonLat, west: f64, east: f64| {
+            (-20.0..=30.0).contains(&c.lat)
+                && if west <= east {
+                    (west..=east).contains(&c.lon)
+                } else {
+                    c.lon >= west || c.lon <= east
+                }
+        };
+        let all: Vec<usize> = (1..=isin.total_bins()).collect();
+        let centers = isin.bin2lonlat(&all).unwrap();
+
+        for &(west, east) in [(-70.0, -60.0), (170.0, -170.0), (-180.0, 180.0)].iter() {
+            let bins = isin.bins_in_bbox(30.0, -20.0, west, east).unwrap();
+            let expected: Vec<usize> = all
+                .iter()
+                .zip(centers.iter())
+                .filter(|(_, c)| inside(c, west, east))
+                .map(|(&b, _)| b)
+                .collect();
+            assert!(!bins.is_empty());
+            assert_eq!(bins, expected);
+        }
+
+        assert!(isin.bins_in_bbox(-20.0, 30.0, 0.0, 10.0).is_err());
+        assert!(isin.bins_in_bbox(30.0, -20.0, 0.0, 181.0).is_err());
+    }
+
+    // Check bins_within_radius matches a brute-force search
+    #[test]
+    fn test_bins_within_radius() {
+        use l3bin::geo_math::{haversine_km, EARTH_RADIUS_KM};
+
+        let isin = Isin::new(180);
+        let all: Vec<usize> = (1..=isin.total_bins()).collect();
+        let centers = isin.bin2lonlat(&all).unwrap();
+
+        for &(lon, lat, km) in [
+            (179.5, 10.0, 800.0),
+            (0.0, 85.0, 900.0),
+            (-60.0, -30.0, 50.0),
+        ]
+        .iter()
+        {
+            let bins = isin.bins_within_radius(lon, lat, km).unwrap();
+            let expected: Vec<usize> = all
+                .iter()
+                .zip(centers.iter())
+                .filter(|(_, c)| haversine_km((lon, lat), (c.lon, c.lat), EARTH_RADIUS_KM) <= km)
+                .map(|(&b, _)| b)
+                .collect();
+            assert_eq!(bins, expected);
+        }
+
+        let isin = Isin::new(4320);
+        let center = isin.bin_to_lonlat(2963106).unwrap();
+        assert_eq!(
+            isin.bins_within_radius(center.lon, center.lat, 0.1)
+                .unwrap(),
+            vec![2963106]
+        );
+        assert!(isin.bins_within_radius(0.0, 0.0, -1.0).is_err());
+    }
+
+    // Check distances between cities and between the bins containing them
+    #[test]
+    fn test_bin_distance_km() {
+        use l3bin::geo_math::{haversine_km, EARTH_RADIUS_KM};
+
+        let isin = Isin::new(4320);
+        let pairs = [
+            ((-0.1278, 51.5074), (2.3522, 48.8566), 343.6),
+            ((-74.0060, 40.7128), (-118.2437, 34.0522), 3935.7),
+            ((151.2093, -33.8688), (174.7633, -36.8485), 2155.9),
+        ];
+
+        for &(a, b, km) in pairs.iter() {
+            assert!((haversine_km(a, b, EARTH_RADIUS_KM) - km).abs() < 1.0);
+
+            let bin_a = isin.lonlat_to_bin(a.0, a.1).unwrap();
+            let bin_b = isin.lonlat_to_bin(b.0, b.1).unwrap();
+            // Bin centers are at most about 3.3 km from the cities
+            assert!((isin.bin_distance_km(bin_a, bin_b).unwrap() - km).abs() < 7.0);
+        }
+
+        assert_eq!(isin.bin_distance_km(245535, 245535).unwrap(), 0.0);
+        assert!(isin.bin_distance_km(0, 1).is_err());
+    }
+
+    // Check neighbors share an edge at mid-latitude and next to a pole
+    #[test]
+    fn test_neighbors() {
+        let isin = Isin::new(4320);
+        let bin = isin.lonlat_to_bin(-63.5, 45.0).unwrap();
+        let neighbors = isin.neighbors(bin).unwrap();
+
+        assert!(neighbors.len() >= 4 && neighbors.len() <= 6);
+        for &other in neighbors.iter() {
+            assert!(isin.shared_edge_length_km(bin, other).unwrap() > 0.0);
+            assert!(isin.neighbors(other).unwrap().contains(&bin));
+        }
+        assert!(neighbors.contains(&(bin - 1)) && neighbors.contains(&(bin + 1)));
+
+        // The southernmost row has 3 bins, the next one 9
+        let isin = Isin::new(18);
+        assert_eq!(isin.neighbors(1).unwrap(), vec![2, 3, 4, 5, 6]);
+        assert_eq!(isin.neighbors(3).unwrap(), vec![1, 2, 10, 11, 12]);
+        assert_eq!(isin.neighbors(412).unwrap(), vec![407, 408, 409, 410, 411]);
+        assert!(isin.neighbors(413).is_err());
+    }
+
+    // Check the GeoJSON export has one closed polygon feature per bin
+    #[cfg(feature = "geojson")]
+    #[test]
+    fn test_bins_to_geojson() {
+        let isin = Isin::new(18);
+        let geojson = isin.bins_to_geojson(&[1, 412]).unwrap();
+
+        assert!(geojson.starts_with(r#"{"type":"FeatureCollection","features":["#));
+        assert_eq!(geojson.matches(r#""type":"Feature","#).count(), 2);
+        assert_eq!(geojson.matches(r#""type":"Polygon""#).count(), 2);
+        assert!(geojson.contains(r#""properties":{"bin":412}"#));
+        assert!(geojson.contains(
+            "[[[-180.00000000,-90.00000000],[-60.00000000,-90.00000000],\
+             [-60.00000000,-80.00000000],[-180.00000000,-80.00000000],\
+             [-180.00000000,-90.00000000]]]"
+        ));
+
+        assert_eq!(
+            isin.bins_to_geojson(&[]).unwrap(),
+            r#"{"type":"FeatureCollection","features":[]}"#
+        );
+        assert!(isin.bins_to_geojson(&[413]).is_err());
+    }
+
+    // Check bin_to_wkt writes a closed lon-first box
+    #[test]
+    fn test_bin_to_wkt() {
+        let isin = Isin::new(18);
+        assert_eq!(
+            isin.bin_to_wkt(412).unwrap(),
+            "POLYGON((60.00000000 80.00000000, 180.00000000 80.00000000, \
+             180.00000000 90.00000000, 60.00000000 90.00000000, 60.00000000 80.00000000))"
+        );
+        assert_eq!(
+            isin.bins_to_wkt(&[1, 412]).unwrap(),
+            vec![isin.bin_to_wkt(1).unwrap(), isin.bin_to_wkt(412).unwrap()]
+        );
+        assert!(isin.bin_to_wkt(0).is_err());
+        assert!(isin.bins_to_wkt(&[1, 413]).is_err());
+    }
+
+    // Check the bin areas add up to the surface of the Earth
+    #[test]
+    fn test_bin_area_km2() {
+        let isin = Isin::new(4320);
+        let total: f64 = (0..isin.num_rows())
+            .map(|row| isin.row_area_km2(row).unwrap() * isin.bins_in_row(row).unwrap() as f64)
+            .sum();
+        let earth = 4.0 * std::f64::consts::PI * 6371.0 * 6371.0;
+        assert!((total - earth).abs() / earth < 1e-9);
+
+        // MODIS bins are about 4.6 km on a side
+        let km2 = isin
+            .bin_area_km2(isin.lonlat_to_bin(0.0, 0.0).unwrap())
+            .unwrap();
+        assert!(km2 > 21.0 && km2 < 22.0);
+        assert_eq!(km2, isin.row_area_km2(2160).unwrap());
+
+        assert!(isin.bin_area_km2(0).is_err());
+        assert_eq!(isin.row_area_km2(4320), None);
+    }
+
+    // Check nearest_bin can differ from the containing bin near a row edge
+    #[test]
+    fn test_nearest_bin() {
+        let isin = Isin::new(18);
+
+        // Bin 1 spans 120 degrees and its center is at (-120, -85), far from this point on its
+        // northern edge, while bin 4 has its center at (-160, -75)
+        assert_eq!(isin.lonlat_to_bin(-170.0, -80.5).unwrap(), 1);
+        assert_eq!(isin.nearest_bin(-170.0, -80.5).unwrap(), 4);
+
+        // At a bin center both agree
+        let center = isin.bin_to_lonlat(200).unwrap();
+        assert_eq!(isin.nearest_bin(center.lon, center.lat).unwrap(), 200);
+
+        assert!(isin.nearest_bin(0.0, -91.0).is_err());
+    }
+
+    // Check the latitude index agrees with the full grid
+    #[test]
+    fn test_lat_index() {
+        let isin = Isin::new(4320);
+        let index = Isin::builder(4320).build_lat_index().unwrap();
+        assert_eq!(index, LatIndex::new(4320));
+        assert_eq!(index.num_rows(), isin.num_rows());
+
+        for row in 0..4320 {
+            assert_eq!(index.bins_in_row(row), isin.bins_in_row(row));
+            assert_eq!(index.row_latitude(row), isin.row_latitude(row));
+        }
+        for &lat in [-90.0, -45.3, 0.0, 12.7, 90.0].iter() {
+            assert_eq!(index.lat2row(lat), isin.lat2row(lat));
+        }
+
+        assert_eq!(index.bins_in_row(4320), None);
+        assert!(index.lat2row(90.5).is_err());
+        assert!(std::mem::size_of::<LatIndex>() < 16);
+
+        // A zero-row index, whose lat2row would have no row to return, cannot be created
+        assert!(LatIndex::try_new(0).is_err());
+        assert!(Isin::builder(0).build_lat_index().is_err());
+        assert!(std::panic::catch_unwind(|| LatIndex::new(0)).is_err());
+        let single = LatIndex::try_new(1).unwrap();
+        assert_eq!(single.lat2row(-90.0).unwrap(), 1);
+        assert_eq!(single.lat2row(90.0).unwrap(), 1);
+    }
+
+    // Check shared grids are cached per standard satellite only
+    #[test]
+    fn test_shared() {
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+        let a = Isin::shared(Satellite::Modis).unwrap();
+        let b = std::thread::spawn(|| Isin::shared(Satellite::Modis).unwrap())
+            .join()
+            .unwrap();
+        assert!(std::sync::Arc::ptr_eq(&a, &b));
+        assert_send_sync(&a);
+
+        let seawifs = Isin::shared(Satellite::Seawifs).unwrap();
+        assert!(!std::sync::Arc::ptr_eq(&a, &seawifs));
+        assert_eq!(seawifs.num_rows(), 2160);
+
+        let c = Isin::shared(Satellite::Custom(18)).unwrap();
+        let d = Isin::shared(Satellite::Custom(18)).unwrap();
+        assert!(!std::sync::Arc::ptr_eq(&c, &d));
+        assert_eq!(c.total_bins(), 412);
+
+        // An invalid custom grid is an error rather than a panic
+        assert!(Isin::shared(Satellite::Custom(0)).is_err());
+    }
+
+    // Check satellite names parse and round-trip through Display
+    #[test]
+    fn test_satellite_from_str() {
+        let known = [
+            ("modis", Satellite::Modis),
+            ("SeaWiFS", Satellite::Seawifs),
+            ("VIIRS", Satellite::Viirs),
+            ("meris", Satellite::Meris),
+            ("czcs", Satellite::Czcs),
+            ("sentinel3", Satellite::Sentinel3),
+            ("Sentinel-3", Satellite::Sentinel3),
+            ("PACE-OCI", Satellite::PaceOci),
+            ("oci", Satellite::PaceOci),
+            ("HawkEye", Satellite::HawkEye),
+            ("octs", Satellite::Octs),
+            ("custom:2160", Satellite::Custom(2160)),
+        ];
+
+        for &(name, sat) in known.iter() {
+            assert_eq!(name.parse::<Satellite>().unwrap(), sat);
+            assert_eq!(sat.to_string().parse::<Satellite>().unwrap(), sat);
+        }
+        assert_eq!(Satellite::Sentinel3.to_string(), "sentinel3");
+
+        let err = "landsat".parse::<Satellite>().unwrap_err();
+        assert_eq!(err.to_string(), "unknown satellite \"landsat\"");
+        assert!("custom:".parse::<Satellite>().is_err());
+        assert!("custom:-1".parse::<Satellite>().is_err());
+        assert!("custom:0".parse::<Satellite>().is_err());
+    }
+
+    // Check the row counts of the built-in sensors
+    #[test]
+    fn test_satellite_num_latitude_rows() {
+        assert_eq!(Satellite::PaceOci.num_latitude_rows(), 4320);
+        assert_eq!(Satellite::HawkEye.num_latitude_rows(), 4320);
+        assert_eq!(Satellite::Octs.num_latitude_rows(), 2160);
+        assert_eq!(
+            Satellite::PaceOci.num_latitude_rows(),
+            Satellite::Modis.num_latitude_rows()
+        );
+        assert_eq!(Satellite::Custom(8640).num_latitude_rows(), 8640);
+    }
+
+    // Check a grid without rows is rejected instead of panicking
+    #[test]
+    fn test_zero_rows() {
+        use l3bin::errors::SatelliteError;
+
+        assert_eq!(
+            Satellite::with_num_latitude_rows(0),
+            Err(SatelliteError::ZeroRows)
+        );
+        assert_eq!(
+            Satellite::with_num_latitude_rows(2160),
+            Ok(Satellite::Custom(2160))
+        );
+
+        assert!(Isin::try_new(0).is_err());
+        assert!(std::panic::catch_unwind(|| Isin::try_new(0)).is_ok());
+        assert_eq!(Isin::try_new(1).unwrap().total_bins(), 2);
+    }
+
+    // Check the bin ranges of the rows tile the whole grid
+    #[test]
+    fn test_row_bin_range() {
+        let isin = Isin::new(2160);
+        let mut next = 1;
+
+        for row in 0..isin.num_rows() {
+            let range = isin.row_bin_range(row).unwrap();
+            assert_eq!(*range.start(), next);
+            assert_eq!(range.clone().count(), isin.bins_in_row(row).unwrap());
+            next = range.end() + 1;
+        }
+
+        assert_eq!(next - 1, isin.total_bins());
+        assert_eq!(isin.row_bin_range(2160), None);
+    }
+
+    // Check row_for_bin at the first and last bin of several rows
+    #[test]
+    fn test_row_for_bin() {
+        let isin = Isin::new(4320);
+
+        for &row in [0, 1, 280, 2159, 2160, 4318, 4319].iter() {
+            let range = isin.row_bin_range(row).unwrap();
+            assert_eq!(isin.row_for_bin(*range.start()).unwrap(), row);
+            assert_eq!(isin.row_for_bin(*range.end()).unwrap(), row);
+        }
+
+        assert_eq!(isin.row_for_bin(245535).unwrap(), 280);
+        assert!(isin.row_for_bin(0).is_err());
+        assert!(isin.row_for_bin(23761677).is_err());
+
+        let zero = Isin::builder(4320).bin_base(BinBase::Zero).build();
+        assert_eq!(zero.row_for_bin(2).unwrap(), 0);
+        assert_eq!(zero.row_for_bin(3).unwrap(), 1);
+    }
+
+    // Check the 0-360 longitude convention round-trips with -180-180
+    #[test]
+    fn test_lonlat2bin_360() {
+        use l3bin::bounds_checker::normalize_longitude;
+
+        assert_eq!(normalize_longitude(-180.0, true), 180.0);
+        assert_eq!(normalize_longitude(180.0, true), 180.0);
+        assert_eq!(normalize_longitude(360.0, false), 0.0);
+        assert_eq!(normalize_longitude(180.0, false), 180.0);
+        assert_eq!(normalize_longitude(12.5, true), 12.5);
+
+        let isin = Isin::new(4320);
+        let lon = [0.0, 12.5, 179.9, 180.0, 200.0, 359.9, 360.0];
+        let lat = [0.0, 10.0, -20.0, 30.0, -40.0, 50.0, 60.0];
+        let bins = isin.lonlat2bin_360(&lon, &lat).unwrap();
+        let lon_180: Vec<f64> = lon.iter().map(|&l| normalize_longitude(l, false)).collect();
+        assert_eq!(bins, isin.lonlat2bin(&lon_180, &lat).unwrap());
+
+        let centers = isin.bin2lonlat_360(&bins).unwrap();
+        let lon: Vec<f64> = centers.iter().map(|c| c.lon).collect();
+        let lat: Vec<f64> = centers.iter().map(|c| c.lat).collect();
+        assert!(lon.iter().all(|l| (0.0..360.0).contains(l)));
+        assert_eq!(isin.lonlat2bin_360(&lon, &lat).unwrap(), bins);
+
+        assert!(isin.lonlat2bin_360(&[-1.0], &[0.0]).is_err());
+        assert!(isin.lonlat2bin_360(&[360.5], &[0.0]).is_err());
+    }
+
+    // Check the typed bin API matches the raw one
+    #[test]
+    fn test_bin_typed() {
+        use l3bin::Bin;
+
+        let isin = Isin::new(4320);
+        let bin = isin.lonlat_to_bin_typed(-63.5, 45.0).unwrap();
+        let raw = isin.lonlat_to_bin(-63.5, 45.0).unwrap();
+
+        assert_eq!(bin, Bin::from(raw));
+        assert_eq!(usize::from(bin), raw);
+        assert_eq!(bin.to_string(), raw.to_string());
+        assert_eq!(
+            isin.bin_to_lonlat_typed(bin).unwrap(),
+            isin.bin_to_lonlat(raw).unwrap()
+        );
+        assert_eq!(
+            isin.bin_to_bounds_typed(bin).unwrap(),
+            isin.bin_to_bounds(raw).unwrap()
+        );
+        assert_eq!(
+            isin.row_for_bin_typed(bin).unwrap(),
+            isin.row_for_bin(raw).unwrap()
+        );
+        assert!(Bin(1) < Bin(2));
+        assert!(isin.bin_to_lonlat_typed(Bin(0)).is_err());
+    }
+
+    // Check the corners of a bin form a ring around its center
+    #[test]
+    fn test_bin_corners() {
+        let isin = Isin::new(4320);
+
+        for &bin in [1, 245535, 2963106, 23761676].iter() {
+            let corners = isin.bin_corners(bin).unwrap();
+            let bounds = isin.bin_to_bounds(bin).unwrap();
+
+            // Consecutive corners share a coordinate, closing back on the first corner
+            for i in 0..4 {
+                let (a, b) = (corners[i], corners[(i + 1) % 4]);
+                assert!(a.0 == b.0 || a.1 == b.1);
+            }
+            assert_eq!(corners[0], (bounds.west, bounds.north));
+            assert_eq!(corners[2], (bounds.east, bounds.south));
+
+            let lon = corners.iter().map(|c| c.0).sum::<f64>() / 4.0;
+            let lat = corners.iter().map(|c| c.1).sum::<f64>() / 4.0;
+            let center = isin.bin_to_lonlat(bin).unwrap();
+            assert!((lon - center.lon).abs() < 1e-9);
+            assert!((lat - center.lat).abs() < 1e-9);
+        }
+
+        assert!(isin.bin_corners(0).is_err());
+    }
+
+    // Check the grid summary
+    #[test]
+    fn test_isin_display() {
+        assert_eq!(
+            Isin::new(18).to_string(),
+            "ISIN grid: 18 rows, 412 total bins, bins/row range 3..=36"
+        );
+        assert_eq!(
+            Isin::new(4320).to_string(),
+            "ISIN grid: 4320 rows, 23761676 total bins, bins/row range 3..=8640"
+        );
+    }
+
+    // Check rasterize samples the bin under each cell center
+    #[test]
+    fn test_rasterize() {
+        let isin = Isin::new(18);
+        let raster = isin
+            .rasterize(&[1, 412], &[1.0, 2.0], 36, 18, -1.0)
+            .unwrap();
+        assert_eq!(raster.len(), 36 * 18);
+
+        // Bin 1 spans 120 degrees at the south-west of the southernmost row, bin 412 the
+        // north-east of the northernmost row, and the raster cells are 10 degrees
+        assert_eq!(raster[35], 2.0);
+        assert_eq!(raster[17 * 36], 1.0);
+        assert_eq!(raster.iter().filter(|&&v| v == 1.0).count(), 12);
+        assert_eq!(raster.iter().filter(|&&v| v == -1.0).count(), 36 * 18 - 24);
+
+        assert!(isin.rasterize(&[1], &[], 4, 2, 0.0).is_err());
+        assert!(isin.rasterize(&[413], &[1.0], 4, 2, 0.0).is_err());
+        assert!(isin.rasterize(&[1], &[1.0], 0, 2, 0.0).is_err());
+    }
+
+    // Check remapping MODIS equator bins to SeaWiFS
+    #[test]
+    fn test_remap_bins() {
+        let modis = Isin::new(Satellite::Modis.num_latitude_rows());
+        let seawifs = Isin::new(Satellite::Seawifs.num_latitude_rows());
+        let equator = modis.bins_on_parallel(0.1).unwrap();
+
+        let remapped = modis.remap_bins(&seawifs, &equator).unwrap();
+        assert_eq!(remapped.len(), equator.len());
+
+        let row = seawifs
+            .row_bin_range(seawifs.lat2row(0.1).unwrap() - 1)
+            .unwrap();
+        assert!(remapped.iter().all(|b| row.contains(b)));
+
+        // Two MODIS bins per SeaWiFS bin along the equator
+        let mut unique = remapped.clone();
+        unique.dedup();
+        assert_eq!(unique.len(), seawifs.bins_on_parallel(0.1).unwrap().len());
+
+        assert!(modis.remap_bins(&seawifs, &[0]).is_err());
+    }
+
+    // Check per-bin statistics of point observations
+    #[test]
+    fn test_bin_stats() {
+        let isin = Isin::new(4320);
+        let lon = [-63.5, -63.501, 120.0];
+        let lat = [45.0, 45.001, -30.0];
+        let stats = isin.bin_stats(&lon, &lat, &[1.0, 2.0, 5.0]).unwrap();
+
+        let bins = isin.lonlat2bin(&lon, &lat).unwrap();
+        assert_eq!(bins[0], bins[1]);
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats.count(bins[0]), 2);
+        assert_eq!(stats.sum(bins[0]), Some(3.0));
+        assert_eq!(stats.mean(bins[0]), Some(1.5));
+        assert_eq!(stats.mean(bins[2]), Some(5.0));
+        assert_eq!(stats.mean(1), None);
+        assert_eq!(stats.count(1), 0);
+
+        let mut expected = vec![bins[0], bins[2]];
+        expected.sort_unstable();
+        assert_eq!(stats.bins(), expected);
+
+        assert!(isin.bin_stats(&lon, &lat, &[1.0]).is_err());
+        assert!(isin.bin_stats(&[0.0], &[95.0], &[1.0]).is_err());
+        assert!(isin.bin_stats(&[], &[], &[]).unwrap().is_empty());
+    }
+
+    // Check iterating over a grid yields every bin center once
+    #[test]
+    fn test_into_iterator() {
+        let isin = Isin::new(18);
+        let iter = (&isin).into_iter();
+        assert_eq!(iter.len(), isin.total_bins());
+
+        let mut count = 0;
+        for (bin, lon, lat) in &isin {
+            if count == 0 {
+                assert_eq!((bin, lon, lat), (1, -120.0, -85.0));
+            }
+            count += 1;
+        }
+        assert_eq!(count, 412);
+
+        let mut iter = isin.iter_centers();
+        iter.nth(409);
+        assert_eq!(iter.len(), 2);
+        let centers: Vec<(usize, f64, f64)> = iter.collect();
+        assert_eq!(centers.last().unwrap().0, 412);
+    }
+
+    // Check k_nearest_bins agrees with nearest_bin and a brute-force search
+    #[test]
+    fn test_k_nearest_bins() {
+        use l3bin::geo_math::{haversine_km, EARTH_RADIUS_KM};
+
+        let isin = Isin::new(180);
+        let all: Vec<usize> = (1..=isin.total_bins()).collect();
+        let centers = isin.bin2lonlat(&all).unwrap();
+
+        for &(lon, lat) in [(-63.5, 45.2), (179.9, -10.0), (10.0, 89.5)].iter() {
+            let nearest = isin.k_nearest_bins(lon, lat, 10).unwrap();
+            assert_eq!(nearest.len(), 10);
+            assert_eq!(nearest[0].0, isin.nearest_bin(lon, lat).unwrap());
+            assert_eq!(isin.k_nearest_bins(lon, lat, 1).unwrap(), nearest[..1]);
+
+            let mut expected: Vec<f64> = centers
+                .iter()
+                .map(|c| haversine_km((lon, lat), (c.lon, c.lat), EARTH_RADIUS_KM))
+                .collect();
+            expected.sort_by(|a, b| a.total_cmp(b));
+            for (found, d) in nearest.iter().zip(expected.iter()) {
+                assert!((found.1 - d).abs() < 1e-9);
+            }
+        }
+
+        // More bins than the grid has
+        let small = Isin::new(2);
+        assert_eq!(
+            small.k_nearest_bins(0.0, 0.0, 100).unwrap().len(),
+            small.total_bins()
+        );
+        assert!(isin.k_nearest_bins(0.0, 0.0, 0).unwrap().is_empty());
+        assert!(isin.k_nearest_bins(0.0, 91.0, 1).is_err());
+    }
+
+    // Check bin_contains at the center, on the edges and just outside
+    #[test]
+    fn test_bin_contains() {
+        let isin = Isin::new(18);
+        // Bin 5 spans -140 to -100 and -80 to -70
+        assert!(isin.bin_contains(5, -120.0, -75.0).unwrap());
+
+        for &(lon, lat) in [
+            (-140.0, -75.0),
+            (-100.0, -75.0),
+            (-120.0, -80.0),
+            (-120.0, -70.0),
+        ]
+        .iter()
+        {
+            assert!(isin.bin_contains(5, lon, lat).unwrap());
+        }
+        for &(lon, lat) in [
+            (-140.1, -75.0),
+            (-99.9, -75.0),
+            (-120.0, -80.1),
+            (-120.0, -69.9),
+        ]
+        .iter()
+        {
+            assert!(!isin.bin_contains(5, lon, lat).unwrap());
+        }
+
+        // Bins on both sides of the antimeridian
+        assert!(isin.bin_contains(4, -180.0, -75.0).unwrap());
+        assert!(isin.bin_contains(12, 180.0, -75.0).unwrap());
+
+        assert!(isin.bin_contains(0, 0.0, 0.0).is_err());
+        assert!(isin.bin_contains(5, 0.0, 91.0).is_err());
+    }
+
+    // Check the hemispheres of bins next to and on the equator and the prime meridian
+    #[test]
+    fn test_bin_hemisphere() {
+        use l3bin::Hemisphere;
+
+        // 18 rows: the equator is a row edge, and row 1 has 9 bins with bin 8 centered on 0
+        let isin = Isin::new(18);
+        let north = isin.lonlat_to_bin(10.0, 0.0).unwrap();
+        let south = isin.lonlat_to_bin(10.0, -0.1).unwrap();
+        assert_eq!(isin.bin_hemisphere(north).unwrap().0, Hemisphere::North);
+        assert_eq!(isin.bin_hemisphere(south).unwrap().0, Hemisphere::South);
+        assert_eq!(
+            isin.bin_hemisphere(8).unwrap(),
+            (Hemisphere::South, Hemisphere::PrimeMeridian)
+        );
+        assert_eq!(isin.bin_hemisphere(9).unwrap().1, Hemisphere::East);
+
+        // 17 rows: the middle row is centered on the equator
+        let isin = Isin::new(17);
+        let bin = isin.lonlat_to_bin(-10.0, 0.0).unwrap();
+        assert_eq!(
+            isin.bin_hemisphere(bin).unwrap(),
+            (Hemisphere::Equator, Hemisphere::West)
+        );
+
+        assert!(isin.bin_hemisphere(0).is_err());
+    }
+
+    // Check the f32 grid gives the same bins and centers within 1e-4 degrees of the f64 grid
+    #[test]
+    fn test_isin_f32() {
+        use l3bin::IsinF32;
+
+        let isin = Isin::new(4320);
+        let lite = IsinF32::new(4320);
+        assert_eq!(lite.total_bins(), isin.total_bins());
+        assert_eq!(lite.num_rows(), 4320);
+
+        for bin in (1..=isin.total_bins()).step_by(9973) {
+            let (lon, lat) = lite.bin_to_lonlat(bin).unwrap();
+            let center = isin.bin_to_lonlat(bin).unwrap();
+            assert!((lon as f64 - center.lon).abs() < 1e-4);
+            assert!((lat as f64 - center.lat).abs() < 1e-4);
+            assert_eq!(lite.lonlat_to_bin(lon, lat).unwrap(), bin);
+        }
+
+        assert!(lite.bin_to_lonlat(0).is_err());
+        assert!(lite.lonlat_to_bin(181.0, 0.0).is_err());
+
+        // The f32 grid shares the row checks of Isin
+        assert!(IsinF32::try_new(0).is_err());
+        assert_eq!(
+            IsinF32::try_new(usize::MAX / 2).unwrap_err(),
+            IsinError::GridTooLarge {
+                numrows: usize::MAX / 2
+            }
+        );
+    }
+
+    // Check that grids with too many bins for a usize are rejected instead of overflowing
+    #[test]
+    fn test_try_new_grid_too_large() {
+        assert_eq!(
+            Isin::try_new(usize::MAX / 2).unwrap_err(),
+            IsinError::GridTooLarge {
+                numrows: usize::MAX / 2
+            }
+        );
+        // About 2 * numrows^2 bins do not fit when numrows is the square root of usize::MAX
+        assert!(Isin::try_new((usize::MAX as f64).sqrt() as usize).is_err());
+
+        assert_eq!(Isin::try_new(43200).unwrap().num_rows(), 43200);
+    }
+
+    // Check the resolution of the standard grids and picking a grid by resolution
+    #[test]
+    fn test_satellite_resolution() {
+        assert_eq!(Satellite::Modis.resolution_degrees(), 180.0 / 4320.0);
+        // 180 / 4320 degrees is 4.63 km, the "4 km" product
+        assert!((Satellite::Modis.nominal_resolution_km() - 4.63).abs() < 0.01);
+        assert!((Satellite::Seawifs.nominal_resolution_km() - 9.27).abs() < 0.01);
+
+        let sat = Satellite::from_resolution_km(9.0).unwrap();
+        assert_eq!(sat.num_latitude_rows(), 2160);
+        assert_eq!(
+            Satellite::from_resolution_km(4.17)
+                .unwrap()
+                .num_latitude_rows(),
+            4680
+        );
+        assert_eq!(
+            Satellite::from_resolution_km(1000.0)
+                .unwrap()
+                .num_latitude_rows(),
+            360
+        );
+        assert!(Satellite::from_resolution_km(0.0).is_err());
+        assert!(Satellite::from_resolution_km(f64::NAN).is_err());
+    }
+
+    // Check that bins at the antimeridian give a single box with in-range longitudes
+    #[test]
+    fn test_bin_to_split_bounds() {
+        let isin = Isin::new(4320);
+
+        for lon in [-180.0, -179.999, 179.999, 180.0] {
+            for lat in [-89.99, -45.0, 0.0, 60.0, 89.99] {
+                let bin = isin.lonlat_to_bin(lon, lat).unwrap();
+                let boxes = isin.bin_to_split_bounds(bin).unwrap();
+                assert_eq!(boxes.len(), 1);
+
+                let (_, _, west, east) = boxes[0];
+                assert!((-180.0..=180.0).contains(&west));
+                assert!((-180.0..=180.0).contains(&east));
+                assert!(west < east);
+            }
+        }
+
+        let east_most = isin.lonlat_to_bin(180.0, 0.0).unwrap();
+        assert_eq!(isin.bin_to_split_bounds(east_most).unwrap()[0].3, 180.0);
+        assert!(isin.bin_to_split_bounds(0).is_err());
+    }
+
+    // Check the CSV converter appends a bin column and stops on malformed rows
+    #[cfg(feature = "csv")]
+    #[test]
+    fn test_lonlat_csv_to_bins() {
+        let isin = Isin::new(18);
+        let data = "lon,lat\n\
+                    -180.0,-90.0\n\
+                    \n\
+                    180.0,90.0\n";
+
+        let mut output = Vec::new();
+        l3bin::csv::lonlat_csv_to_bins(&isin, data.as_bytes(), &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "lon,lat,bin\n-180.0,-90.0,1\n180.0,90.0,412\n"
+        );
+
+        let mut output = Vec::new();
+        let err = l3bin::csv::lonlat_csv_to_bins(&isin, "0,0\nbad\n".as_bytes(), &mut output);
+        assert!(matches!(err, Err(IsinError::InvalidParameter(_))));
+
+        let err = l3bin::csv::lonlat_csv_to_bins(&isin, "0,91\n".as_bytes(), Vec::new());
+        assert!(matches!(err, Err(IsinError::InvalidLatitude { .. })));
+    }
+
+    // Check the strict conversion tolerates float noise at the antimeridian but not real errors
+    #[test]
+    fn test_lonlat2bin_strict() {
+        let isin = Isin::new(4320);
+        let edge = isin.lonlat2bin(&[180.0], &[0.0]).unwrap();
+
+        assert_eq!(isin.lonlat2bin_strict(&[180.0], &[0.0]).unwrap(), edge);
+        assert_eq!(
+            isin.lonlat2bin_strict(&[180.0000001], &[0.0]).unwrap(),
+            edge
+        );
+        assert!(isin.lonlat2bin(&[180.0000001], &[0.0]).is_err());
+        assert!(matches!(
+            isin.lonlat2bin_strict(&[200.0], &[0.0]),
+            Err(IsinError::InvalidLongitude { .. })
+        ));
+        assert!(isin.lonlat2bin_strict(&[f64::NAN], &[0.0]).is_err());
+        assert!(isin.lonlat2bin_strict(&[0.0], &[91.0]).is_err());
+    }
+
+    // Check that a path along the equator crosses increasing bins of the same row
+    #[test]
+    fn test_bins_along_path() {
+        let isin = Isin::new(4320);
+        let path = isin
+            .bins_along_path(&[(-10.0, 0.001), (0.0, 0.001), (10.0, 0.001)], 1.0)
+            .unwrap();
+
+        let row = isin.row_for_bin(path[0]).unwrap();
+        assert!(path.iter().all(|&b| isin.row_for_bin(b).unwrap() == row));
+        assert!(path.windows(2).all(|w| w[1] == w[0] + 1));
+        assert_eq!(path[0], isin.lonlat_to_bin(-10.0, 0.001).unwrap());
+        assert_eq!(
+            *path.last().unwrap(),
+            isin.lonlat_to_bin(10.0, 0.001).unwrap()
+        );
+
+        assert!(isin.bins_along_path(&[], 1.0).unwrap().is_empty());
+        assert!(isin.bins_along_path(&[(0.0, 0.0)], 0.0).is_err());
+        assert!(isin.bins_along_path(&[(0.0, 95.0)], 1.0).is_err());
+    }
+
+    // Check that compressed runs of bins break at row boundaries and expand back
+    #[test]
+    fn test_compress_bins() {
+        let isin = Isin::new(18);
+
+        // Row 1 is 4..=12 and row 2 starts at 13
+        let bins = vec![13, 10, 11, 12, 14, 20, 11];
+        let ranges = isin.compress_bins(&bins).unwrap();
+        assert_eq!(ranges, vec![(10, 12), (13, 14), (20, 20)]);
+        assert_eq!(
+            isin.decompress_bins(&ranges).unwrap(),
+            vec![10, 11, 12, 13, 14, 20]
+        );
+
+        assert!(isin.compress_bins(&[]).unwrap().is_empty());
+        assert!(isin.compress_bins(&[413]).is_err());
+        assert!(isin.decompress_bins(&[(5, 4)]).is_err());
+        assert!(isin.decompress_bins(&[(1, 413)]).is_err());
+    }
+
+    // Check BinList records round-trip and map to grid bins
+    #[test]
+    fn test_binlist() {
+        use l3bin::binlist::{parse_binlist, write_binlist};
+        use l3bin::BinListEntry;
+
+        let entries = vec![
+            BinListEntry {
+                bin_num: 245535,
+                nobs: 12,
+                nscenes: 2,
+                weights: 3.5,
+                flags: 0,
+            },
+            BinListEntry {
+                bin_num: 245536,
+                nobs: 1,
+                nscenes: 1,
+                weights: 1.0,
+                flags: 4,
+            },
+        ];
+
+        let bytes = write_binlist(&entries);
+        assert_eq!(bytes.len(), 26);
+        assert_eq!(&bytes[..4], &245535u32.to_le_bytes());
+        assert_eq!(parse_binlist(&bytes).unwrap(), entries);
+
+        let isin = Isin::new(4320);
+        let center = isin.bin_to_lonlat(entries[0].bin_num as usize).unwrap();
+        assert_eq!(isin.lonlat_to_bin(center.lon, center.lat).unwrap(), 245535);
+
+        assert!(parse_binlist(&bytes[..20]).is_err());
+        assert!(parse_binlist(&[]).unwrap().is_empty());
+    }
+
+    // Check that latitude bands cover whole rows and the full band covers every bin
+    #[test]
+    fn test_bins_in_lat_band() {
+        let isin = Isin::new(18);
+        assert_eq!(
+            isin.bins_in_lat_band(-90.0, 90.0).unwrap().len(),
+            isin.total_bins()
+        );
+        // -75 is in row 1 (4..=12) and -65 in row 2
+        let bins = isin.bins_in_lat_band(-75.0, -65.0).unwrap();
+        assert_eq!(bins[0], 4);
+        assert_eq!(bins.len(), 9 + isin.bins_in_row(2).unwrap());
+        assert_eq!(
+            isin.bins_in_lat_band(90.0, 90.0).unwrap(),
+            vec![410, 411, 412]
+        );
+
+        assert!(isin.bins_in_lat_band(10.0, -10.0).is_err());
+        assert!(isin.bins_in_lat_band(-91.0, 0.0).is_err());
+    }
+
+    // Check the bounding box of bins, crossing the antimeridian when that is shorter
+    #[test]
+    fn test_bins_bounding_box() {
+        let isin = Isin::new(4320);
+        let west_edge = isin.lonlat_to_bin(-179.99, 10.0).unwrap();
+        let east_edge = isin.lonlat_to_bin(179.99, 10.0).unwrap();
+        let greenwich = isin.lonlat_to_bin(0.01, 10.0).unwrap();
+
+        // Two bins around the dateline give a small box crossing it
+        let (north, south, west, east) = isin.bins_bounding_box(&[west_edge, east_edge]).unwrap();
+        assert!(west > east);
+        assert!(west > 179.9 && east < -179.9);
+        assert!(north > 10.0 && south < 10.0);
+
+        // Adding a bin at the prime meridian makes the box stop at the widest gap
+        let (_, _, west, east) = isin
+            .bins_bounding_box(&[west_edge, east_edge, greenwich])
+            .unwrap();
+        assert!(west > east);
+        assert_eq!(west, 0.0);
+        assert!(east < -179.9);
+
+        let (_, _, west, east) = isin.bins_bounding_box(&[greenwich]).unwrap();
+        assert!(west <= 0.01 && east >= 0.01 && west < east);
+
+        assert!(isin.bins_bounding_box(&[]).is_err());
+        assert!(isin.bins_bounding_box(&[0]).is_err());
+    }
+
+    // Check downsampling MODIS by 2 gives the SeaWiFS grid and maps bins by their center
+    #[test]
+    fn test_downsample_to() {
+        let modis = Isin::new(4320);
+        let coarse = modis.downsample_to(2).unwrap();
+        assert_eq!(coarse.num_rows(), 2160);
+        assert_eq!(coarse.total_bins(), Isin::new(2160).total_bins());
+
+        let center = modis.bin_to_lonlat(245535).unwrap();
+        assert_eq!(
+            modis.map_bin_down(&coarse, 245535).unwrap(),
+            coarse.lonlat_to_bin(center.lon, center.lat).unwrap()
+        );
+
+        assert!(modis.downsample_to(0).is_err());
+        assert!(modis.downsample_to(7).is_err());
+        // 4320 / 16 = 270 rows is not a multiple of 360
+        assert!(modis.downsample_to(16).is_err());
+        assert!(modis.map_bin_down(&coarse, 0).is_err());
+    }
+
+    // Check that grids compare equal when built with the same rows and numbering
+    #[test]
+    fn test_isin_eq() {
+        let rows = Satellite::Modis.num_latitude_rows();
+        let isin = Isin::new(rows);
+        assert_eq!(isin, Isin::new(rows));
+        assert_eq!(isin.clone(), isin);
+
+        assert_ne!(isin, Isin::new(2160));
+        assert_ne!(isin, Isin::builder(rows).bin_base(BinBase::Zero).build());
+    }
+
+    // Check that flooring the fractional position gives the row and column of the bin
+    #[test]
+    fn test_lonlat_to_fractional() {
+        let isin = Isin::new(4320);
+
+        for (lon, lat) in [(45.0, 45.0), (-179.9, -89.9), (12.34, -0.01), (-60.5, 70.2)] {
+            let (row, col) = isin.lonlat_to_fractional(lon, lat).unwrap();
+            let bin = isin.lonlat_to_bin(lon, lat).unwrap();
+            assert_eq!(
+                (row.floor() as usize, col.floor() as usize),
+                isin.bin_to_rowcol(bin).unwrap()
+            );
+        }
+
+        let (row, _) = isin.lonlat_to_fractional(0.0, 90.0).unwrap();
+        assert_eq!(row, 4320.0);
+        assert!(isin.lonlat_to_fractional(0.0, 91.0).is_err());
+    }
+
+    // Check interpolation of a uniform field and of a linear gradient between bin centers
+    #[test]
+    fn test_interpolate() {
+        use std::collections::HashMap;
+
+        let isin = Isin::new(180);
+        let centers = isin
+            .bin2lonlat(&(1..=isin.total_bins()).collect::<Vec<_>>())
+            .unwrap();
+
+        let uniform: HashMap<usize, f64> = (1..=isin.total_bins()).map(|b| (b, 4.5)).collect();
+        for (lon, lat) in [(0.0, 0.0), (179.9, 45.3), (-180.0, -89.9), (33.3, 90.0)] {
+            let value = isin.interpolate(&uniform, lon, lat).unwrap().unwrap();
+            assert!((value - 4.5).abs() < 1e-12);
+        }
+
+        // A field linear in longitude and latitude is reproduced away from the poles and the
+        // antimeridian, even between rows with different numbers of bins
+        let gradient: HashMap<usize, f64> = centers
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (i + 1, 2.0 * c.lon + 3.0 * c.lat))
+            .collect();
+        for (lon, lat) in [(10.2, 20.7), (-100.45, -60.3), (150.0, 75.1)] {
+            let value = isin.interpolate(&gradient, lon, lat).unwrap().unwrap();
+            assert!((value - (2.0 * lon + 3.0 * lat)).abs() < 1e-9);
+        }
+
+        assert_eq!(isin.interpolate(&HashMap::new(), 0.0, 0.0).unwrap(), None);
+        assert!(isin.interpolate(&uniform, 200.0, 0.0).is_err());
+    }
+
+    // Check every standard satellite is listed once and round-trips its name
+    #[test]
+    fn test_satellite_all() {
+        let all = Satellite::all();
+        assert_eq!(all.len(), 9);
+        assert!(!all.iter().any(|s| matches!(s, Satellite::Custom(_))));
+
+        for (i, sat) in all.iter().enumerate() {
+            assert_eq!(sat.to_string().parse::<Satellite>().unwrap(), *sat);
+            assert!(!all[..i].contains(sat));
+        }
+    }
+
+    // Check the standard grids have a multiple of 360 rows, while any positive number of rows
+    // still gives a consistent grid
+    #[test]
+    fn test_all_satellites_divisible_by_360() {
+        for sat in Satellite::all() {
+            assert_eq!(sat.num_latitude_rows() % 360, 0, "{}", sat);
+        }
+
+        for numrows in [1, 7, 18, 1001] {
+            let sat = Satellite::with_num_latitude_rows(numrows).unwrap();
+            assert!(Isin::new(sat.num_latitude_rows())
+                .check_invariants()
+                .is_ok());
+        }
+    }
+
+    // Check that points near the poles map to the 3 wide bins of the polar rows
+    #[test]
+    fn test_polar_rows() {
+        let isin = Isin::new(4320);
+        let last = isin.total_bins();
+
+        assert_eq!(isin.lonlat2bin(&[-179.0], &[-89.99]).unwrap(), vec![1]);
+        assert_eq!(isin.lonlat2bin(&[0.0], &[-89.99]).unwrap(), vec![2]);
+        assert_eq!(isin.lonlat2bin(&[179.0], &[89.99]).unwrap(), vec![last]);
+        assert_eq!(isin.lonlat2bin(&[0.0], &[90.0]).unwrap(), vec![last - 1]);
+
+        let center = isin.bin_to_lonlat(1).unwrap();
+        assert_eq!(center.lon, -120.0);
+        assert!(center.lat < -89.97);
+        assert!(isin.is_polar_row(isin.row_for_bin(1).unwrap()));
+        assert!(isin.is_polar_row(isin.row_for_bin(last).unwrap()));
+        assert!(!isin.is_polar_row(isin.row_for_bin(4).unwrap()));
+        assert_eq!(isin.bins_in_row(0), Some(3));
+    }
+
+    // Check radius searches wider than a quarter of the circumference against a brute force
+    #[test]
+    fn test_bins_within_large_radius() {
+        use l3bin::geo_math::{haversine_km, EARTH_RADIUS_KM};
+
+        let isin = Isin::new(180);
+
+        for &(lon, lat, km) in &[
+            (0.0, 0.0, 12000.0),
+            (0.0, 0.0, 15000.0),
+            (0.0, 0.0, 21000.0),
+            (45.0, 60.0, 6000.0),
+            (-120.0, -40.0, 18000.0),
+        ] {
+            let expected: Vec<usize> = isin
+                .iter_centers()
+                .filter(|&(_, c_lon, c_lat)| {
+                    haversine_km((lon, lat), (c_lon, c_lat), EARTH_RADIUS_KM) <= km
+                })
+                .map(|(bin, _, _)| bin)
+                .collect();
+            assert_eq!(isin.bins_within_radius(lon, lat, km).unwrap(), expected);
+        }
+
+        // Half of the circumference or more covers every bin
+        let half = std::f64::consts::PI * EARTH_RADIUS_KM;
+        assert_eq!(
+            isin.bins_within_radius(10.0, 20.0, half).unwrap().len(),
+            isin.total_bins()
+        );
+    }
+
+    // Check every lonlat to bin variant uses the 0-based numbering of a BinBase::Zero grid
+    #[test]
+    fn test_bin_base_zero_lonlat_variants() {
+        let zero = Isin::builder(18).bin_base(BinBase::Zero).build();
+        let lon = [-170.0, 10.0, 170.0];
+        let lat = [-85.0, 0.5, 89.0];
+
+        let expected = zero.lonlat2bin(&lon, &lat).unwrap();
+        assert_eq!(expected, vec![0, 225, 411]);
+
+        assert_eq!(zero.lonlat2bin_checked(&lon, &lat).unwrap(), expected);
+        assert_eq!(zero.lonlat2bin_swath(&lon, &lat, false).unwrap(), expected);
+        assert_eq!(zero.lonlat2bin_swath(&lon, &lat, true).unwrap(), expected);
+        assert_eq!(zero.lonlat2bin_sorted_by_lat(&lon, &lat).unwrap(), expected);
+        assert_eq!(zero.lonlat2bin_strict(&lon, &lat).unwrap(), expected);
+        assert_eq!(zero.lonlat2bin_clamped(&lon, &lat), expected);
+        let lon_360: Vec<f64> = lon.iter().map(|l| (l + 360.0) % 360.0).collect();
+        assert_eq!(zero.lonlat2bin_360(&lon_360, &lat).unwrap(), expected);
+        for i in 0..lon.len() {
+            assert_eq!(zero.lonlat_to_bin(lon[i], lat[i]).unwrap(), expected[i]);
+        }
+    }
+
+    // Check the rasterize and CF coordinate callbacks run once per row and end with all done
+    #[test]
+    fn test_progress_callbacks() {
+        let isin = Isin::new(18);
+
+        let calls = std::cell::RefCell::new(Vec::new());
+        let report = |done: usize, total: usize| calls.borrow_mut().push((done, total));
+        let raster = isin
+            .rasterize_with_progress(&[1], &[1.0], 36, 18, 0.0, Some(&report))
+            .unwrap();
+        assert_eq!(raster, isin.rasterize(&[1], &[1.0], 36, 18, 0.0).unwrap());
+        let rows = calls.replace(Vec::new());
+        assert_eq!(rows.len(), 18);
+        assert_eq!(rows[0], (36, 36 * 18));
+        assert_eq!(rows[17], (36 * 18, 36 * 18));
+
+        let coordinates = isin.cf_coordinates_with_progress(Some(&report));
+        assert_eq!(coordinates, isin.cf_coordinates());
+        let rows = calls.into_inner();
+        assert_eq!(rows.len(), 18);
+        assert_eq!(rows[17], (412, 412));
+    }
+
+    // Check that methods using the NASA numbering refuse a Zero-based grid instead of mixing bases
+    #[test]
+    fn test_bin_base_zero_rejected() {
+        let zero = Isin::builder(18).bin_base(BinBase::Zero).build();
+        let one = Isin::new(18);
+        let bin = zero.lonlat_to_bin(10.0, 45.0).unwrap();
+
+        assert!(matches!(
+            zero.neighbors(bin),
+            Err(IsinError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            zero.nearest_bin(10.0, 45.0),
+            Err(IsinError::InvalidParameter(_))
+        ));
+        assert!(zero.bins_in_bbox(50.0, 40.0, 0.0, 20.0).is_err());
+        assert!(zero.iter_spiral(bin, 1).is_err());
+        assert!(zero.remap_bins(&one, &[bin]).is_err());
+        assert!(one.remap_bins(&zero, &[bin]).is_err());
+        assert!(zero.build_adjacency_csr().is_err());
+
+        // The same calls work on the 1-based grid
+        assert!(one.neighbors(bin + 1).is_ok());
+        assert_eq!(one.nearest_bin(10.0, 45.0).unwrap(), bin + 1);
+
+        // Multi-grid conversions follow the numbering of each grid
+        let bins = l3bin::bin_pyramid(&[zero, one], &[10.0], &[45.0]).unwrap();
+        assert_eq!(bins, vec![vec![bin], vec![bin + 1]]);
+    }
 }